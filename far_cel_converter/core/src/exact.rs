@@ -0,0 +1,443 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Signed, Zero};
+
+use crate::units::{Dimension, Registry, Unit};
+
+// Significant figures shown for a non-terminating result without --sig.
+const DEFAULT_SIG: u32 = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rounding {
+    HalfEven,
+    HalfUp,
+    HalfDown,
+    Up,
+    Down,
+    Ceiling,
+    Floor,
+}
+
+impl Rounding {
+    pub fn from_name(name: &str) -> Option<Rounding> {
+        match name {
+            "half-even" => Some(Rounding::HalfEven),
+            "half-up" => Some(Rounding::HalfUp),
+            "half-down" => Some(Rounding::HalfDown),
+            "up" => Some(Rounding::Up),
+            "down" => Some(Rounding::Down),
+            "ceiling" => Some(Rounding::Ceiling),
+            "floor" => Some(Rounding::Floor),
+            _ => None,
+        }
+    }
+}
+
+fn pow10(exp: u32) -> BigInt {
+    num_traits::pow(BigInt::from(10), exp as usize)
+}
+
+// Largest power of ten a decimal may carry. Far beyond any physical
+// quantity, and it keeps a typo like 1e999999999 from building a number with
+// a billion digits.
+const MAX_EXPONENT: i64 = 4000;
+
+// Parses a plain decimal such as "-98.6" or "1.5e-3" without going through f64.
+pub fn parse_decimal(text: &str) -> Result<BigRational, String> {
+    let not_a_number = || format!("'{text}' is not a number");
+    let text = text.trim();
+    let (mantissa, exp) = match text.find(['e', 'E']) {
+        Some(i) => (
+            &text[..i],
+            text[i + 1..].parse::<i64>().map_err(|_| not_a_number())?,
+        ),
+        None => (text, 0),
+    };
+    let (negative, mantissa) = match mantissa.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+    };
+    let (whole, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if whole.is_empty() && frac.is_empty()
+        || !whole
+            .chars()
+            .chain(frac.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(not_a_number());
+    }
+
+    let digits: BigInt = format!("{whole}{frac}")
+        .parse()
+        .map_err(|_| not_a_number())?;
+    let scale = exp
+        .checked_sub(frac.len() as i64)
+        .filter(|scale| scale.unsigned_abs() <= MAX_EXPONENT as u64)
+        .ok_or_else(|| format!("the exponent of '{text}' is out of range"))?;
+    let mut value = BigRational::from_integer(digits);
+    if scale >= 0 {
+        value *= BigRational::from_integer(pow10(scale as u32));
+    } else {
+        value /= BigRational::from_integer(pow10(scale.unsigned_abs() as u32));
+    }
+    Ok(if negative { -value } else { value })
+}
+
+// The shortest decimal that round-trips, so 0.0254 becomes exactly 254/10000
+// rather than the nearest binary fraction.
+pub fn from_f64(value: f64) -> Result<BigRational, String> {
+    if !value.is_finite() {
+        return Err(format!("{value} is too large to show exactly"));
+    }
+    parse_decimal(&value.to_string())
+}
+
+// Exact factor and offset of a unit. Units whose factor is not a terminating
+// decimal, like the 5/9 of Fahrenheit, carry their own fractions.
+pub fn exact_scale(unit: &Unit) -> Result<(BigRational, BigRational), String> {
+    match &unit.exact {
+        Some((factor, offset)) => Ok((factor.clone(), offset.clone())),
+        None => Ok((from_f64(unit.factor)?, from_f64(unit.offset)?)),
+    }
+}
+
+pub fn convert(value: &BigRational, from: &Unit, to: &Unit) -> Result<BigRational, String> {
+    if from.dimension != to.dimension {
+        return Err(format!(
+            "cannot convert {} to {}, which is {}",
+            from.dimension.describe(),
+            to.symbol,
+            to.dimension.describe()
+        ));
+    }
+    let (from_factor, from_offset) = exact_scale(from)?;
+    let (to_factor, to_offset) = exact_scale(to)?;
+    let base = value * from_factor + from_offset;
+    if from.dimension == Dimension::TEMPERATURE && base.is_negative() {
+        return Err(format!("{value} {} is below absolute zero", from.symbol));
+    }
+    Ok((base - to_offset) / to_factor)
+}
+
+// Converts an entry of the form "98.6F [to C]". Exact mode is limited to a
+// single linear conversion, arithmetic goes through the f64 evaluator.
+pub fn convert_entry(
+    entry: &str,
+    default_from: Option<&Unit>,
+    default_to: Option<&Unit>,
+    registry: &Registry,
+) -> Result<(BigRational, Unit), String> {
+    let words: Vec<&str> = entry.split_whitespace().collect();
+    let (quantity, target) = match words.as_slice() {
+        [quantity @ .., keyword, target] if ["to", "in", "as"].contains(keyword) => {
+            (quantity.join(""), Some(*target))
+        }
+        quantity => (quantity.join(""), None),
+    };
+
+    let split = quantity
+        .trim_end_matches(|c: char| c.is_alphabetic() || c == '°')
+        .len();
+    let (number, suffix) = quantity.split_at(split);
+    // Only text that is no number at all gets the usage hint, an exponent
+    // out of range says so.
+    let value = parse_decimal(number).map_err(|e| {
+        if e == format!("'{number}' is not a number") {
+            format!("exact mode converts one value like 98.6F --to C, got '{entry}'")
+        } else {
+            e
+        }
+    })?;
+
+    let lookup = |name: &str| registry.find(name).cloned();
+    let from = match suffix {
+        "" => default_from
+            .cloned()
+            .ok_or_else(|| format!("'{entry}' has no unit, add one or use --from"))?,
+        name => lookup(name)?,
+    };
+    let to = match target {
+        Some(name) => lookup(name)?,
+        None => match default_to {
+            Some(unit) => unit.clone(),
+            None => registry
+                .opposite(&from)
+                .cloned()
+                .ok_or("exact mode needs a target unit")?,
+        },
+    };
+    Ok((convert(&value, &from, &to)?, to))
+}
+
+fn round_to_integer(value: &BigRational, mode: Rounding) -> BigInt {
+    let down = value.trunc().to_integer();
+    let rest = (value - BigRational::from_integer(down.clone())).abs();
+    if rest.is_zero() {
+        return down;
+    }
+    let away = if value.is_negative() {
+        &down - 1
+    } else {
+        &down + 1
+    };
+    let half = BigRational::new(BigInt::one(), BigInt::from(2));
+    let go_away = match mode {
+        Rounding::Down => false,
+        Rounding::Up => true,
+        Rounding::Ceiling => value.is_positive(),
+        Rounding::Floor => value.is_negative(),
+        Rounding::HalfUp => rest >= half,
+        Rounding::HalfDown => rest > half,
+        Rounding::HalfEven => rest > half || rest == half && (&down % 2u32) != BigInt::zero(),
+    };
+    if go_away {
+        away
+    } else {
+        down
+    }
+}
+
+// value * 10^places, for negative places too.
+fn shift(value: &BigRational, places: i32) -> BigRational {
+    let scale = BigRational::from_integer(pow10(places.unsigned_abs()));
+    if places >= 0 {
+        value * scale
+    } else {
+        value / scale
+    }
+}
+
+// Power of ten of the leading digit, e.g. 1 for 340/9 = 37.7...
+fn magnitude(value: &BigRational) -> i32 {
+    let value = value.abs();
+    let one = BigRational::one();
+    let mut exp = value.numer().to_string().len() as i32 - value.denom().to_string().len() as i32;
+    while shift(&one, exp) > value {
+        exp -= 1;
+    }
+    while shift(&one, exp + 1) <= value {
+        exp += 1;
+    }
+    exp
+}
+
+// Shows a value with `sig` significant figures, keeping trailing zeros.
+pub fn format_sig(value: &BigRational, sig: u32, mode: Rounding) -> String {
+    if value.is_zero() {
+        return if sig > 1 {
+            format!("0.{}", "0".repeat(sig as usize - 1))
+        } else {
+            "0".to_string()
+        };
+    }
+
+    // Number of digits after the decimal point, negative for tens, hundreds, ...
+    let mut places = sig as i32 - 1 - magnitude(value);
+    let mut digits = round_to_integer(&shift(value, places), mode);
+    // Rounding 9.99 up to 10.0 adds a digit.
+    if digits.abs() >= pow10(sig) {
+        places -= 1;
+        digits = round_to_integer(&shift(value, places), mode);
+    }
+
+    decimal_text(&digits, places)
+}
+
+// Writes digits * 10^-places as a decimal string.
+fn decimal_text(digits: &BigInt, places: i32) -> String {
+    let mut text = digits.abs().to_string();
+    if places > 0 {
+        let places = places as usize;
+        if text.len() <= places {
+            text = format!("{}{text}", "0".repeat(places + 1 - text.len()));
+        }
+        text.insert(text.len() - places, '.');
+    } else {
+        text.push_str(&"0".repeat(places.unsigned_abs() as usize));
+    }
+    if digits.is_negative() {
+        text.insert(0, '-');
+    }
+    text
+}
+
+// The full decimal expansion, if it terminates (340/9 does not).
+fn terminating(value: &BigRational) -> Option<String> {
+    (0..=64).find_map(|places| {
+        let scaled = shift(value, places);
+        scaled
+            .is_integer()
+            .then(|| decimal_text(&scaled.to_integer(), places))
+    })
+}
+
+// Fraction form for when the decimal had to be rounded, e.g. "340/9".
+fn fraction(value: &BigRational) -> String {
+    if value.is_integer() {
+        value.numer().to_string()
+    } else {
+        format!("{}/{}", value.numer(), value.denom())
+    }
+}
+
+// "37.78 C (exact: 340/9)" with `sig` significant figures, or the full
+// decimal when no precision is asked for and the expansion terminates.
+pub fn describe(value: &BigRational, unit: &Unit, sig: Option<u32>, mode: Rounding) -> String {
+    let shown = match sig {
+        Some(sig) => format_sig(value, sig, mode),
+        None => terminating(value).unwrap_or_else(|| format_sig(value, DEFAULT_SIG, mode)),
+    };
+    if parse_decimal(&shown).is_ok_and(|shown| &shown == value) {
+        format!("{shown} {}", unit.symbol)
+    } else {
+        format!("{shown} {} (exact: {})", unit.symbol, fraction(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exact(entry: &str) -> String {
+        let registry = Registry::builtin();
+        let (value, unit) = convert_entry(entry, None, None, &registry).unwrap();
+        format!("{} {}", fraction(&value), unit.symbol)
+    }
+
+    fn rational(text: &str) -> BigRational {
+        parse_decimal(text).unwrap()
+    }
+
+    #[test]
+    fn temperature_reference_points() {
+        assert_eq!(exact("98.6F to C"), "37 C");
+        assert_eq!(exact("-40F to C"), "-40 C");
+        assert_eq!(exact("212F to K"), "7463/20 K");
+        assert_eq!(exact("0K to F"), "-45967/100 F");
+        assert_eq!(exact("0C to R"), "49167/100 R");
+        assert_eq!(exact("100F to C"), "340/9 C");
+    }
+
+    #[test]
+    fn length_and_mass_reference_points() {
+        assert_eq!(exact("1 in to cm"), "127/50 cm");
+        assert_eq!(exact("1 mi to km"), "25146/15625 km");
+        assert_eq!(exact("1 lb to g"), "45359237/100000 g");
+        assert_eq!(exact("1 ft to in"), "12 in");
+    }
+
+    #[test]
+    fn round_trip_is_lossless() {
+        let registry = Registry::builtin();
+        let f = registry.lookup("F").unwrap();
+        let c = registry.lookup("C").unwrap();
+        let value = rational("98.123456789");
+        let there = convert(&value, f, c).unwrap();
+        assert_eq!(convert(&there, c, f).unwrap(), value);
+    }
+
+    #[test]
+    fn exponents_out_of_range() {
+        let registry = Registry::builtin();
+        for entry in ["1.5e-2147483648 m to cm", "1e999999999 m to cm"] {
+            let error = convert_entry(entry, None, None, &registry).unwrap_err();
+            assert!(error.contains("is out of range"), "{error}");
+        }
+        assert_eq!(
+            parse_decimal("1e-9223372036854775808"),
+            Err("the exponent of '1e-9223372036854775808' is out of range".to_string())
+        );
+        assert_eq!(
+            parse_decimal("2.5e4002"),
+            Err("the exponent of '2.5e4002' is out of range".to_string())
+        );
+        assert!(parse_decimal("25e3999").is_ok());
+        assert_eq!(parse_decimal("1e"), Err("'1e' is not a number".to_string()));
+        assert!(convert_entry("x to cm", None, None, &registry)
+            .unwrap_err()
+            .starts_with("exact mode converts one value"));
+    }
+
+    #[test]
+    fn non_finite_values_are_errors() {
+        assert_eq!(from_f64(0.0254), Ok(rational("0.0254")));
+        assert!(from_f64(f64::INFINITY).is_err());
+        assert!(from_f64(f64::NAN).is_err());
+        assert!(from_f64(f64::MAX).is_ok());
+    }
+
+    #[test]
+    fn significant_figures() {
+        let value = BigRational::new(340.into(), 9.into());
+        assert_eq!(format_sig(&value, 4, Rounding::HalfEven), "37.78");
+        assert_eq!(format_sig(&value, 1, Rounding::HalfEven), "40");
+        assert_eq!(format_sig(&rational("37"), 4, Rounding::HalfEven), "37.00");
+        assert_eq!(
+            format_sig(&rational("0.000123456"), 3, Rounding::HalfEven),
+            "0.000123"
+        );
+        assert_eq!(
+            format_sig(&rational("9.996"), 3, Rounding::HalfEven),
+            "10.0"
+        );
+        assert_eq!(
+            format_sig(&rational("-459.67"), 3, Rounding::HalfEven),
+            "-460"
+        );
+        assert_eq!(
+            format_sig(&rational("12345"), 2, Rounding::HalfEven),
+            "12000"
+        );
+    }
+
+    #[test]
+    fn describe_marks_rounded_results() {
+        let registry = Registry::builtin();
+        let c = registry.lookup("C").unwrap();
+        let value = BigRational::new(340.into(), 9.into());
+        assert_eq!(
+            describe(&rational("37"), c, None, Rounding::HalfEven),
+            "37 C"
+        );
+        assert_eq!(
+            describe(&rational("36.75"), c, Some(3), Rounding::HalfEven),
+            "36.8 C (exact: 147/4)"
+        );
+        assert_eq!(
+            describe(&value, c, None, Rounding::HalfEven),
+            "37.7777777778 C (exact: 340/9)"
+        );
+    }
+
+    #[test]
+    fn rounding_modes() {
+        let cases = [
+            ("2.5", ["2", "3", "2", "3", "2", "3", "2"]),
+            ("-2.5", ["-2", "-3", "-2", "-3", "-2", "-2", "-3"]),
+            ("3.5", ["4", "4", "3", "4", "3", "4", "3"]),
+            ("2.4", ["2", "2", "2", "3", "2", "3", "2"]),
+        ];
+        let modes = [
+            Rounding::HalfEven,
+            Rounding::HalfUp,
+            Rounding::HalfDown,
+            Rounding::Up,
+            Rounding::Down,
+            Rounding::Ceiling,
+            Rounding::Floor,
+        ];
+        for (input, expected) in cases {
+            for (mode, want) in modes.iter().zip(expected) {
+                assert_eq!(
+                    format_sig(&rational(input), 1, *mode),
+                    want,
+                    "{input} {mode:?}"
+                );
+            }
+        }
+    }
+}
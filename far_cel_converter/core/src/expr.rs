@@ -0,0 +1,761 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::temperature::Kelvin;
+use crate::units::{format_number, Dimension, Registry, Unit};
+
+// Words that introduce the target unit, as in "3 ft + 20 in to cm".
+const KEYWORDS: [&str; 3] = ["to", "in", "as"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Word(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{n}"),
+            Token::Word(w) => write!(f, "{w}"),
+            Token::Plus => write!(f, "+"),
+            Token::Minus => write!(f, "-"),
+            Token::Star => write!(f, "*"),
+            Token::Slash => write!(f, "/"),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+        }
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '°' || c == '_'
+}
+
+// Hyphens join words only when the result names a unit, so "psi-gauge" is
+// one unit while "x-y" stays a subtraction.
+fn tokenize(input: &str, registry: &Registry) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let single = match c {
+            '+' => Some(Token::Plus),
+            '-' => Some(Token::Minus),
+            '*' => Some(Token::Star),
+            '/' => Some(Token::Slash),
+            '(' => Some(Token::LParen),
+            ')' => Some(Token::RParen),
+            _ => None,
+        };
+        if let Some(token) = single {
+            tokens.push(token);
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // Only treat 'e' as an exponent when digits follow, so "2e" stays 2 of unit "e".
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let mut j = i + 1;
+                if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    i = j;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            let number = text
+                .parse()
+                .map_err(|_| format!("'{text}' is not a number"))?;
+            tokens.push(Token::Number(number));
+        } else if is_word_char(c) {
+            let start = i;
+            while i < chars.len() && is_word_char(chars[i]) {
+                i += 1;
+            }
+            let mut end = i;
+            while end + 1 < chars.len() && chars[end] == '-' && is_word_char(chars[end + 1]) {
+                end += 1;
+                while end < chars.len() && is_word_char(chars[end]) {
+                    end += 1;
+                }
+            }
+            let hyphenated: String = chars[start..end].iter().collect();
+            if end > i && registry.lookup(&hyphenated).is_some() {
+                i = end;
+            }
+            tokens.push(Token::Word(chars[start..i].iter().collect()));
+        } else {
+            return Err(format!("unexpected character '{c}'"));
+        }
+    }
+    Ok(tokens)
+}
+
+// Named results kept between REPL lines, including "ans".
+pub type Variables = BTreeMap<String, Quantity>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    // Magnitude in base units (m, kg, s, K).
+    pub value: f64,
+    pub dimension: Dimension,
+    // True for a reading, false for a difference between two of them, so
+    // that "72F - 32F" is 40 degrees and never gets the +32 offset applied
+    // again on conversion. Only temperatures and units with an offset (like
+    // a gauge pressure) tell the two apart.
+    pub absolute: bool,
+    // Unit the result is shown in: the first unit written in a sum.
+    pub unit: Option<Unit>,
+}
+
+impl Quantity {
+    pub fn number(value: f64) -> Quantity {
+        Quantity {
+            value,
+            dimension: Dimension::NONE,
+            absolute: false,
+            unit: None,
+        }
+    }
+
+    pub fn of(value: f64, unit: &Unit) -> Quantity {
+        Quantity {
+            value: unit.to_base(value),
+            dimension: unit.dimension,
+            absolute: true,
+            unit: Some(unit.clone()),
+        }
+    }
+
+    // Differences leave out the offset of the unit, readings take it away.
+    pub fn in_unit(&self, unit: &Unit) -> f64 {
+        if self.absolute {
+            unit.to_unit(self.value)
+        } else {
+            self.value / unit.factor
+        }
+    }
+
+    pub fn convert_to(self, unit: &Unit) -> Result<Quantity, String> {
+        if self.dimension != unit.dimension {
+            return Err(format!(
+                "cannot convert {} to {}, which is {}",
+                self.dimension.describe(),
+                unit.symbol,
+                unit.dimension.describe()
+            ));
+        }
+        Ok(Quantity {
+            unit: Some(unit.clone()),
+            ..self
+        })
+    }
+
+    // The number shown to the user, in the display unit if there is one.
+    pub fn display_value(&self) -> f64 {
+        match &self.unit {
+            Some(unit) => self.in_unit(unit),
+            None => self.value,
+        }
+    }
+
+    // Readings below absolute zero are rejected wherever they appear.
+    pub fn checked(self) -> Result<Quantity, String> {
+        let temperature = self.dimension == Dimension::TEMPERATURE;
+        if temperature && self.absolute && Kelvin::new(self.value).is_err() {
+            return Err(format!("{self} is below absolute zero"));
+        }
+        Ok(self)
+    }
+
+    // Unit written after the number, base units for derived quantities.
+    pub fn label(&self) -> Option<String> {
+        match &self.unit {
+            Some(unit) if !self.absolute && self.has_readings() => {
+                Some(format!("{} delta", unit.symbol))
+            }
+            Some(unit) => Some(unit.symbol.clone()),
+            None if self.dimension == Dimension::NONE => None,
+            None => Some(self.dimension.to_string()),
+        }
+    }
+
+    // Temperatures and units with an offset tell readings from differences.
+    fn has_readings(&self) -> bool {
+        self.dimension == Dimension::TEMPERATURE
+            || self.unit.as_ref().is_some_and(|u| u.offset != 0.0)
+    }
+
+    // What a reading is called in error messages.
+    fn reading(&self) -> &'static str {
+        if self.dimension == Dimension::TEMPERATURE {
+            "temperature"
+        } else {
+            "reading"
+        }
+    }
+
+    fn on_offset_scale(&self) -> bool {
+        self.absolute && self.unit.as_ref().is_some_and(|u| u.offset != 0.0)
+    }
+
+    // A reading that cannot be added to, subtracted from or negated.
+    fn is_reading(&self) -> bool {
+        self.absolute && (self.dimension == Dimension::TEMPERATURE || self.on_offset_scale())
+    }
+
+    fn add(self, rhs: Quantity) -> Result<Quantity, String> {
+        if self.dimension != rhs.dimension {
+            return Err(format!(
+                "cannot add {} and {}",
+                self.dimension.describe(),
+                rhs.dimension.describe()
+            ));
+        }
+        if self.absolute && rhs.absolute && (self.is_reading() || rhs.is_reading()) {
+            return Err(format!(
+                "cannot add two {}s, subtract them to get a difference first",
+                self.reading()
+            ));
+        }
+        Quantity {
+            value: self.value + rhs.value,
+            absolute: self.absolute || rhs.absolute,
+            unit: self.unit.or(rhs.unit),
+            ..self
+        }
+        .checked()
+    }
+
+    fn sub(self, rhs: Quantity) -> Result<Quantity, String> {
+        if self.dimension != rhs.dimension {
+            return Err(format!(
+                "cannot subtract {} from {}",
+                rhs.dimension.describe(),
+                self.dimension.describe()
+            ));
+        }
+        if rhs.is_reading() && !self.absolute {
+            let reading = rhs.reading();
+            return Err(format!(
+                "cannot subtract a {reading} from a {reading} difference"
+            ));
+        }
+        Quantity {
+            value: self.value - rhs.value,
+            absolute: self.absolute && !rhs.absolute,
+            unit: self.unit.or(rhs.unit),
+            ..self
+        }
+        .checked()
+    }
+
+    fn neg(self) -> Result<Quantity, String> {
+        if self.is_reading() {
+            return Err(format!(
+                "cannot negate a {}, write the sign on the number",
+                self.reading()
+            ));
+        }
+        Ok(Quantity {
+            value: -self.value,
+            ..self
+        })
+    }
+
+    // Scaling keeps the unit of the dimensioned side, combining two
+    // dimensioned quantities falls back to base units.
+    fn combine(self, rhs: Quantity, value: f64, dimension: Dimension) -> Result<Quantity, String> {
+        for side in [&self, &rhs] {
+            if side.on_offset_scale() {
+                let hint = if side.dimension == Dimension::TEMPERATURE {
+                    "use K or a difference"
+                } else {
+                    "use a difference"
+                };
+                return Err(format!(
+                    "cannot multiply or divide a {} on an offset scale, {hint}",
+                    side.reading()
+                ));
+            }
+        }
+        if !value.is_finite() {
+            return Err("division by zero".to_string());
+        }
+        let (absolute, unit) = if rhs.dimension == Dimension::NONE {
+            (self.absolute, self.unit)
+        } else if self.dimension == Dimension::NONE && dimension == rhs.dimension {
+            (rhs.absolute, rhs.unit)
+        } else {
+            // Anything but a temperature worked out from other units, such
+            // as a pressure, is a reading.
+            (dimension != Dimension::TEMPERATURE, None)
+        };
+        Ok(Quantity {
+            value,
+            dimension,
+            absolute,
+            unit,
+        })
+    }
+
+    fn mul(self, rhs: Quantity) -> Result<Quantity, String> {
+        let value = self.value * rhs.value;
+        let dimension = self.dimension * rhs.dimension;
+        self.combine(rhs, value, dimension)
+    }
+
+    fn div(self, rhs: Quantity) -> Result<Quantity, String> {
+        let value = self.value / rhs.value;
+        let dimension = self.dimension / rhs.dimension;
+        self.combine(rhs, value, dimension)
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let number = format_number(self.display_value());
+        match self.label() {
+            Some(label) => write!(f, "{number} {label}"),
+            None => write!(f, "{number}"),
+        }
+    }
+}
+
+fn is_keyword(token: Option<&Token>) -> bool {
+    matches!(token, Some(Token::Word(w)) if KEYWORDS.contains(&w.as_str()))
+}
+
+// Recursive descent over:
+//   command := expr [("to" | "in" | "as") expr]
+//   expr    := term (("+" | "-") term)*
+//   term    := factor (("*" | "/") factor)*
+//   factor  := ("-" | "+") factor | primary
+//   primary := number [unit] | unit | "(" expr ")"
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    registry: &'a Registry,
+    vars: &'a Variables,
+    // Inside a target such as "km/h" a unit stands for its size only, so
+    // "C" there means a Celsius degree and not 0 °C.
+    target: bool,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, ahead: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + ahead)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn unit(&self, name: &str) -> Result<&Unit, String> {
+        self.registry.find(name)
+    }
+
+    fn expr(&mut self) -> Result<Quantity, String> {
+        let mut lhs = self.term()?;
+        loop {
+            match self.peek() {
+                Some(Token::Plus) => {
+                    self.pos += 1;
+                    lhs = lhs.add(self.term()?)?;
+                }
+                Some(Token::Minus) => {
+                    self.pos += 1;
+                    lhs = lhs.sub(self.term()?)?;
+                }
+                _ => return Ok(lhs),
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Quantity, String> {
+        let mut lhs = self.factor()?;
+        loop {
+            match self.peek() {
+                Some(Token::Star) => {
+                    self.pos += 1;
+                    lhs = lhs.mul(self.factor()?)?;
+                }
+                Some(Token::Slash) => {
+                    self.pos += 1;
+                    lhs = lhs.div(self.factor()?)?;
+                }
+                _ => return Ok(lhs),
+            }
+        }
+    }
+
+    fn factor(&mut self) -> Result<Quantity, String> {
+        match self.peek() {
+            Some(Token::Minus) => {
+                self.pos += 1;
+                // "-40F" is minus forty degrees, not the negation of 40 °F.
+                if let Some(Token::Number(n)) = self.peek() {
+                    let n = -*n;
+                    self.pos += 1;
+                    return self.number(n);
+                }
+                self.factor()?.neg()
+            }
+            Some(Token::Plus) => {
+                self.pos += 1;
+                self.factor()
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Quantity, String> {
+        match self.next() {
+            Some(Token::Number(n)) => self.number(n),
+            Some(Token::Word(w)) if !self.target && KEYWORDS.contains(&w.as_str()) => {
+                Err(format!("expected a value before '{w}'"))
+            }
+            Some(Token::Word(w)) if !self.target && self.vars.contains_key(&w) => {
+                Ok(self.vars[&w].clone())
+            }
+            Some(Token::Word(w)) => {
+                let unit = self.unit(&w)?;
+                if self.target {
+                    Ok(Quantity {
+                        value: unit.factor,
+                        dimension: unit.dimension,
+                        absolute: false,
+                        unit: None,
+                    })
+                } else {
+                    Ok(Quantity::of(1.0, unit))
+                }
+            }
+            Some(Token::LParen) => {
+                let inner = self.expr()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(inner),
+                    _ => Err("missing ')'".to_string()),
+                }
+            }
+            Some(token) => Err(format!("unexpected '{token}'")),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+
+    // A number takes the word after it as its unit, except for "in"/"to"
+    // followed by another unit: "20 in to cm" is inches, "72F in K" is not.
+    // "10 C delta" is a temperature difference rather than a reading.
+    fn number(&mut self, n: f64) -> Result<Quantity, String> {
+        let name = match self.peek() {
+            Some(Token::Word(w)) if !self.vars.contains_key(w) => w.clone(),
+            _ => return Ok(Quantity::number(n)),
+        };
+        let ahead = self.peek_at(1);
+        if is_keyword(self.peek()) && matches!(ahead, Some(Token::Word(_))) && !is_keyword(ahead) {
+            return Ok(Quantity::number(n));
+        }
+        self.pos += 1;
+        let unit = self.unit(&name)?.clone();
+        if matches!(self.peek(), Some(Token::Word(w)) if w == "delta") {
+            self.pos += 1;
+            return Ok(Quantity {
+                value: n * unit.factor,
+                dimension: unit.dimension,
+                absolute: false,
+                unit: Some(unit),
+            });
+        }
+        Quantity::of(n, &unit).checked()
+    }
+
+    fn target_unit(&mut self) -> Result<Unit, String> {
+        if let (Some(Token::Word(w)), None) = (self.peek(), self.peek_at(1)) {
+            return self.unit(w).cloned();
+        }
+
+        let start = self.pos;
+        self.target = true;
+        let size = self.expr()?;
+        if let Some(token) = self.peek() {
+            return Err(format!("unexpected '{token}'"));
+        }
+        if size.value == 0.0 {
+            return Err("cannot convert to a zero-sized unit".to_string());
+        }
+        let symbol: String = self.tokens[start..].iter().map(|t| t.to_string()).collect();
+        Ok(Unit {
+            name: symbol.clone(),
+            symbol,
+            aliases: Vec::new(),
+            dimension: size.dimension,
+            factor: size.value,
+            offset: 0.0,
+            exact: None,
+        })
+    }
+}
+
+// Evaluates an expression such as "3 ft + 20 in to cm", returning the
+// unconverted result and the target unit, if one was named.
+pub fn evaluate(
+    input: &str,
+    registry: &Registry,
+    vars: &Variables,
+) -> Result<(Quantity, Option<Unit>), String> {
+    let tokens = tokenize(input, registry)?;
+    if tokens.is_empty() {
+        return Err("empty expression".to_string());
+    }
+
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        registry,
+        vars,
+        target: false,
+    };
+    let quantity = parser.expr()?;
+    let target = match parser.peek() {
+        None => None,
+        Some(token) if is_keyword(Some(token)) => {
+            parser.pos += 1;
+            Some(parser.target_unit()?)
+        }
+        Some(token) => return Err(format!("unexpected '{token}'")),
+    };
+    Ok((quantity, target))
+}
+
+// Reads a unit such as "psi" or "lb/in/in" on its own, the way the target
+// of a conversion is read.
+pub fn parse_unit(input: &str, registry: &Registry) -> Result<Unit, String> {
+    let tokens = tokenize(input, registry)?;
+    if tokens.is_empty() {
+        return Err("empty unit".to_string());
+    }
+    let vars = Variables::new();
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        registry,
+        vars: &vars,
+        target: false,
+    };
+    parser.target_unit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    // Evaluates `input` and shows the result the way the REPL does.
+    fn show(registry: &Registry, input: &str) -> Result<String, String> {
+        let (quantity, target) = evaluate(input, registry, &Variables::new())?;
+        let quantity = match target {
+            Some(unit) => quantity.convert_to(&unit)?,
+            None => quantity,
+        };
+        Ok(quantity.to_string())
+    }
+
+    fn eval(input: &str) -> Result<String, String> {
+        show(&Registry::builtin(), input)
+    }
+
+    #[test]
+    fn precedence_and_parentheses() {
+        assert_eq!(eval("2 + 3 * 4"), Ok("14.00".to_string()));
+        assert_eq!(eval("(2 + 3) * 4"), Ok("20.00".to_string()));
+        assert_eq!(eval("10 - 4 - 3"), Ok("3.00".to_string()));
+        assert_eq!(eval("12 / 2 / 3"), Ok("2.00".to_string()));
+        assert_eq!(eval("-(2 + 3) * 2"), Ok("-10.00".to_string()));
+        assert_eq!(eval("2 * -3"), Ok("-6.00".to_string()));
+        assert_eq!(eval("1.5e3 / 3"), Ok("500.00".to_string()));
+        assert_eq!(eval("(2 + 3"), Err("missing ')'".to_string()));
+        assert_eq!(eval("2 +"), Err("unexpected end of expression".to_string()));
+        assert_eq!(eval("2 )"), Err("unexpected ')'".to_string()));
+        assert_eq!(eval("1 / 0"), Err("division by zero".to_string()));
+        assert_eq!(eval("   "), Err("empty expression".to_string()));
+    }
+
+    #[test]
+    fn lengths_add_up_in_the_target_unit() {
+        assert_eq!(eval("3 ft + 20 in to cm"), Ok("142.24 cm".to_string()));
+        assert_eq!(eval("3 ft + 20 in"), Ok("4.67 ft".to_string()));
+        assert_eq!(eval("1 mi / 2 h to mph"), Ok("0.50 mph".to_string()));
+        assert_eq!(eval("90 km / 1.5 h to km/h"), Ok("60.00 km/h".to_string()));
+        assert_eq!(eval("2 m * 3 m"), Ok("6.00 m^2".to_string()));
+        assert_eq!(eval("20 in"), Ok("20.00 in".to_string()));
+        assert_eq!(eval("3 m - 1 m - 1 m"), Ok("1.00 m".to_string()));
+        assert_eq!(eval("-(3 m)"), Ok("-3.00 m".to_string()));
+    }
+
+    #[test]
+    fn hyphens_join_only_known_units() {
+        let mut registry = Registry::builtin();
+        registry.add(Unit::new(
+            "hand span",
+            "hand-span",
+            &[],
+            Dimension::LENGTH,
+            0.2,
+        ));
+        assert_eq!(
+            show(&registry, "2 hand-span to cm"),
+            Ok("40.00 cm".to_string())
+        );
+        // "m-km" is not a unit, so it is meters minus a kilometer.
+        assert_eq!(show(&registry, "1000 m-km"), Ok("0.00 m".to_string()));
+        assert_eq!(show(&registry, "3 m - 1 m"), Ok("2.00 m".to_string()));
+    }
+
+    #[test]
+    fn temperatures_and_differences() {
+        assert_eq!(eval("72F - 32F"), Ok("40.00 F delta".to_string()));
+        assert_eq!(eval("72F - 32F to C"), Ok("22.22 C delta".to_string()));
+        assert_eq!(eval("20C + 10 C delta"), Ok("30.00 C".to_string()));
+        assert_eq!(eval("10 C delta + 20C to F"), Ok("86.00 F".to_string()));
+        assert_eq!(eval("20C - 10 C delta"), Ok("10.00 C".to_string()));
+        assert_eq!(eval("-40F to C"), Ok("-40.00 C".to_string()));
+        assert_eq!(
+            eval("20C + 10C"),
+            Err("cannot add two temperatures, subtract them to get a difference first".to_string())
+        );
+        assert_eq!(
+            eval("10 C delta - 20C"),
+            Err("cannot subtract a temperature from a temperature difference".to_string())
+        );
+        assert_eq!(
+            eval("-(20C)"),
+            Err("cannot negate a temperature, write the sign on the number".to_string())
+        );
+        assert_eq!(
+            eval("2 * 20C"),
+            Err(
+                "cannot multiply or divide a temperature on an offset scale, use K or a difference"
+                    .to_string()
+            )
+        );
+        assert_eq!(eval("2 * 300 K"), Ok("600.00 K".to_string()));
+        assert_eq!(
+            eval("-300C"),
+            Err("-300.00 C is below absolute zero".to_string())
+        );
+        assert_eq!(
+            eval("0 K - 10 C delta"),
+            Err("-10.00 K is below absolute zero".to_string())
+        );
+    }
+
+    #[test]
+    fn readings_on_offset_scales() {
+        let mut registry = Registry::builtin();
+        let pressure = Dimension::MASS / Dimension::LENGTH / Dimension::TIME / Dimension::TIME;
+        registry.add(Unit {
+            offset: 101_325.0,
+            ..Unit::new("gauge psi", "psig", &[], pressure, 6894.757)
+        });
+        let eval = |input| show(&registry, input);
+        assert_eq!(eval("5 psig to psig"), Ok("5.00 psig".to_string()));
+        assert_eq!(eval("5 psig - 3 psig"), Ok("2.00 psig delta".to_string()));
+        assert_eq!(eval("5 psig + 2 psig delta"), Ok("7.00 psig".to_string()));
+        assert_eq!(eval("2 psig delta + 5 psig"), Ok("7.00 psig".to_string()));
+        assert_eq!(eval("5 psig - 2 psig delta"), Ok("3.00 psig".to_string()));
+        assert_eq!(
+            eval("2 * (5 psig - 3 psig)"),
+            Ok("4.00 psig delta".to_string())
+        );
+        assert_eq!(
+            eval("5 psig + 5 psig to psig"),
+            Err("cannot add two readings, subtract them to get a difference first".to_string())
+        );
+        assert_eq!(
+            eval("2 * 5 psig to psig"),
+            Err(
+                "cannot multiply or divide a reading on an offset scale, use a difference"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            eval("2 psig delta - 5 psig"),
+            Err("cannot subtract a reading from a reading difference".to_string())
+        );
+        assert_eq!(
+            eval("-(5 psig)"),
+            Err("cannot negate a reading, write the sign on the number".to_string())
+        );
+    }
+
+    #[test]
+    fn dimension_mismatches() {
+        assert_eq!(
+            eval("1 m + 1 kg"),
+            Err("cannot add length (m) and mass (kg)".to_string())
+        );
+        assert_eq!(
+            eval("1 m - 1 s"),
+            Err("cannot subtract time (s) from length (m)".to_string())
+        );
+        assert_eq!(
+            eval("1 m to kg"),
+            Err("cannot convert length (m) to kg, which is mass (kg)".to_string())
+        );
+        assert_eq!(
+            eval("1 m / 1 s to km"),
+            Err("cannot convert m·s^-1 to km, which is length (m)".to_string())
+        );
+        assert_eq!(
+            eval("2 to m"),
+            Err("cannot convert dimensionless to m, which is length (m)".to_string())
+        );
+    }
+
+    #[test]
+    fn case_folding_needs_two_letters() {
+        assert_eq!(
+            eval("1 KM to M"),
+            Err("unknown unit 'M', did you mean 'm'?".to_string())
+        );
+        assert_eq!(eval("1 KM to m"), Ok("1000.00 m".to_string()));
+        assert_eq!(eval("2 Celsius to K"), Ok("275.15 K".to_string()));
+        assert_eq!(
+            eval("300 k"),
+            Err("unknown unit 'k', did you mean 'K'?".to_string())
+        );
+        assert_eq!(eval("3 Feet to in"), Ok("36.00 in".to_string()));
+        let registry = Registry::builtin();
+        assert!(registry.lookup("k").is_none());
+        assert!(registry.lookup("M").is_none());
+        assert!(registry.lookup("c").is_none());
+        assert_eq!(registry.lookup("Km").map(|u| u.symbol.as_str()), Some("km"));
+        assert_eq!(registry.lookup("MS").map(|u| u.symbol.as_str()), Some("ms"));
+    }
+}
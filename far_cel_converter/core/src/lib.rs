@@ -0,0 +1,53 @@
+// Conversion logic of far_cel_converter: temperature scales, units with
+// dimensions, the expression parser, exact rational conversion and derived
+// weather quantities.
+// Builds without std (it only needs an allocator) for embedding, and with the
+// `wasm-bindgen` feature exports `convert` to JavaScript.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::string::String;
+
+pub mod exact;
+pub mod expr;
+pub mod temperature;
+pub mod units;
+pub mod weather;
+
+use expr::Quantity;
+use units::Registry;
+
+// Converts `value` between two built-in units named by symbol, name or
+// alias, e.g. convert(98.6, "F", "C").
+#[cfg_attr(feature = "wasm-bindgen", wasm_bindgen::prelude::wasm_bindgen)]
+pub fn convert(value: f64, from: &str, to: &str) -> Result<f64, String> {
+    let registry = Registry::builtin();
+    let (from, to) = (registry.find(from)?, registry.find(to)?);
+    Ok(Quantity::of(value, from)
+        .checked()?
+        .convert_to(to)?
+        .display_value())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_between_named_units() {
+        assert!((convert(98.6, "F", "C").unwrap() - 37.0).abs() < 1e-9);
+        assert!((convert(1.0, "mile", "km").unwrap() - 1.609_344).abs() < 1e-12);
+        assert!((convert(0.0, "celsius", "K").unwrap() - 273.15).abs() < 1e-12);
+    }
+
+    #[test]
+    fn reports_bad_input() {
+        assert_eq!(
+            convert(1.0, "F", "parsec").unwrap_err(),
+            "unknown unit 'parsec'"
+        );
+        assert!(convert(1.0, "m", "kg").is_err());
+        assert!(convert(-500.0, "F", "C").is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+use crate::units::Registry;
+
+// Kelvin reading of 0 °C.
+pub const CELSIUS_ZERO: f64 = 273.15;
+
+// Readings on each scale (Celsius, ...) can only be built at or above
+// absolute zero. Temperature differences are not readings, expr.rs keeps
+// them apart as quantities that are not absolute.
+macro_rules! scale {
+    ($reading:ident, $symbol:literal, $absolute_zero:expr) => {
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+        pub struct $reading(f64);
+
+        impl $reading {
+            pub const ABSOLUTE_ZERO: f64 = $absolute_zero;
+
+            pub fn new(value: f64) -> Result<$reading, String> {
+                if value.is_nan() || value < Self::ABSOLUTE_ZERO {
+                    return Err(format!(
+                        "{value:.2} {} is below absolute zero ({:.2} {})",
+                        $symbol,
+                        Self::ABSOLUTE_ZERO,
+                        $symbol
+                    ));
+                }
+                Ok($reading(value))
+            }
+
+            // A reading converted from a valid one. Only rounding can take it
+            // below absolute zero, -5.7e-14 K for -459.67 °F, so it is clamped.
+            fn converted(value: f64) -> $reading {
+                $reading(value.max(Self::ABSOLUTE_ZERO))
+            }
+
+            pub fn value(self) -> f64 {
+                self.0
+            }
+        }
+
+        impl fmt::Display for $reading {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{:.2} {}", self.0, $symbol)
+            }
+        }
+    };
+}
+
+scale!(Celsius, "°C", -CELSIUS_ZERO);
+scale!(Fahrenheit, "°F", -459.67);
+scale!(Kelvin, "K", 0.0);
+
+// Scales are converted through their units in the registry, the same ones
+// expressions use, so there is one formula for each scale.
+fn rescale(value: f64, from: &str, to: &str) -> f64 {
+    let registry = Registry::builtin();
+    match (registry.lookup(from), registry.lookup(to)) {
+        (Some(from), Some(to)) => to.to_unit(from.to_base(value)),
+        _ => unreachable!("{from} and {to} are built-in units"),
+    }
+}
+
+pub fn calc_celsius(y: Fahrenheit) -> Celsius {
+    Celsius::converted(rescale(y.0, "F", "C"))
+}
+
+pub fn calc_farenheit(y: Celsius) -> Fahrenheit {
+    Fahrenheit::converted(rescale(y.0, "C", "F"))
+}
+
+macro_rules! convert {
+    ($from:ident => $to:ident, |$v:ident| $body:expr) => {
+        impl From<$from> for $to {
+            fn from($v: $from) -> $to {
+                $body
+            }
+        }
+    };
+}
+
+convert!(Fahrenheit => Celsius, |t| calc_celsius(t));
+convert!(Celsius => Fahrenheit, |t| calc_farenheit(t));
+convert!(Celsius => Kelvin, |t| Kelvin::converted(rescale(t.0, "C", "K")));
+convert!(Kelvin => Celsius, |t| Celsius::converted(rescale(t.0, "K", "C")));
+convert!(Fahrenheit => Kelvin, |t| Kelvin::converted(rescale(t.0, "F", "K")));
+convert!(Kelvin => Fahrenheit, |t| Fahrenheit::converted(rescale(t.0, "K", "F")));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn reference_points() {
+        let boiling = Celsius::new(100.0).unwrap();
+        assert!(close(Fahrenheit::from(boiling).value(), 212.0));
+        assert!(close(Kelvin::from(boiling).value(), 373.15));
+        let body = Fahrenheit::new(98.6).unwrap();
+        assert!(close(Celsius::from(body).value(), 37.0));
+        let cross = Celsius::new(-40.0).unwrap();
+        assert!(close(Fahrenheit::from(cross).value(), -40.0));
+        assert_eq!(Kelvin::new(0.0).unwrap().to_string(), "0.00 K");
+        // The same numbers expressions give, both go through the registry.
+        for value in [-40.0, 0.0, 36.6, 451.0] {
+            let f = Fahrenheit::new(value).unwrap();
+            assert_eq!(
+                calc_celsius(f).value(),
+                crate::convert(value, "F", "C").unwrap()
+            );
+            let c = Celsius::new(value).unwrap();
+            assert_eq!(
+                calc_farenheit(c).value(),
+                crate::convert(value, "C", "F").unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        for value in [-273.15, -40.0, 0.0, 36.6, 1e6] {
+            let c = Celsius::new(value).unwrap();
+            assert!(close(Celsius::from(Fahrenheit::from(c)).value(), value));
+            assert!(close(Celsius::from(Kelvin::from(c)).value(), value));
+            assert!(close(
+                Celsius::from(Fahrenheit::from(Kelvin::from(c))).value(),
+                value
+            ));
+        }
+    }
+
+    #[test]
+    fn absolute_zero() {
+        assert_eq!(
+            Celsius::new(-300.0).unwrap_err(),
+            "-300.00 °C is below absolute zero (-273.15 °C)"
+        );
+        assert!(Kelvin::new(-0.001).is_err());
+        assert!(Fahrenheit::new(f64::NAN).is_err());
+        // Absolute zero on one scale stays at or above it on every other,
+        // even where the arithmetic rounds below.
+        let zero = Fahrenheit::new(Fahrenheit::ABSOLUTE_ZERO).unwrap();
+        assert_eq!(Kelvin::from(zero).value(), 0.0);
+        assert!(Celsius::from(zero).value() >= Celsius::ABSOLUTE_ZERO);
+        let zero = Kelvin::new(0.0).unwrap();
+        assert!(Fahrenheit::from(zero).value() >= Fahrenheit::ABSOLUTE_ZERO);
+        assert!(Celsius::from(zero).value() >= Celsius::ABSOLUTE_ZERO);
+    }
+}
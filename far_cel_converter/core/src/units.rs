@@ -0,0 +1,391 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Div, Mul};
+
+use num_rational::BigRational;
+
+use crate::temperature::{Fahrenheit, CELSIUS_ZERO};
+
+const BASE_NAMES: [&str; 5] = ["length", "mass", "time", "temperature", "currency"];
+const BASE_SYMBOLS: [&str; 5] = ["m", "kg", "s", "K", "¤"];
+
+// Exponents over the base dimensions above, so a speed is length^1 time^-1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dimension([i8; 5]);
+
+impl Dimension {
+    pub const NONE: Dimension = Dimension([0, 0, 0, 0, 0]);
+    pub const LENGTH: Dimension = Dimension([1, 0, 0, 0, 0]);
+    pub const MASS: Dimension = Dimension([0, 1, 0, 0, 0]);
+    pub const TIME: Dimension = Dimension([0, 0, 1, 0, 0]);
+    pub const TEMPERATURE: Dimension = Dimension([0, 0, 0, 1, 0]);
+    // Measured in the base currency of the loaded rate snapshot.
+    pub const CURRENCY: Dimension = Dimension([0, 0, 0, 0, 1]);
+
+    // Human name for error messages, e.g. "length (m)" or "m·s^-1".
+    pub fn describe(self) -> String {
+        if self == Dimension::NONE {
+            return "dimensionless".to_string();
+        }
+        let mut used = self.0.iter().enumerate().filter(|(_, &e)| e != 0);
+        match (used.next(), used.next()) {
+            (Some((i, 1)), None) => format!("{} ({})", BASE_NAMES[i], BASE_SYMBOLS[i]),
+            _ => self.to_string(),
+        }
+    }
+
+    // Reads a base dimension name such as "length", or "dimensionless".
+    pub fn from_name(name: &str) -> Option<Dimension> {
+        if name == "dimensionless" {
+            return Some(Dimension::NONE);
+        }
+        let at = BASE_NAMES.iter().position(|n| *n == name)?;
+        let mut exps = [0; 5];
+        exps[at] = 1;
+        Some(Dimension(exps))
+    }
+
+    // True when any base dimension of `base` appears in this one, e.g. EUR/kg
+    // involves currency.
+    pub fn involves(self, base: Dimension) -> bool {
+        self.0.iter().zip(base.0).any(|(&e, b)| e != 0 && b != 0)
+    }
+}
+
+impl Mul for Dimension {
+    type Output = Dimension;
+
+    // Multiplying quantities adds the exponents of their dimensions.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, other: Dimension) -> Dimension {
+        let mut exps = self.0;
+        for (e, o) in exps.iter_mut().zip(other.0) {
+            *e += o;
+        }
+        Dimension(exps)
+    }
+}
+
+impl Div for Dimension {
+    type Output = Dimension;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Dimension) -> Dimension {
+        let mut exps = self.0;
+        for (e, o) in exps.iter_mut().zip(other.0) {
+            *e -= o;
+        }
+        Dimension(exps)
+    }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts: Vec<String> = self
+            .0
+            .iter()
+            .zip(BASE_SYMBOLS)
+            .filter(|(&e, _)| e != 0)
+            .map(|(&e, sym)| {
+                if e == 1 {
+                    sym.to_string()
+                } else {
+                    format!("{sym}^{e}")
+                }
+            })
+            .collect();
+        write!(f, "{}", parts.join("·"))
+    }
+}
+
+// A named unit: value_in_base = value * factor + offset.
+// Only temperature scales other than Kelvin and user units such as a gauge
+// pressure have an offset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unit {
+    pub name: String,
+    pub symbol: String,
+    pub aliases: Vec<String>,
+    pub dimension: Dimension,
+    pub factor: f64,
+    pub offset: f64,
+    // Exact factor and offset for exact mode, when the f64 ones are rounded.
+    pub exact: Option<(BigRational, BigRational)>,
+}
+
+impl Unit {
+    pub fn new(
+        name: &str,
+        symbol: &str,
+        aliases: &[&str],
+        dimension: Dimension,
+        factor: f64,
+    ) -> Unit {
+        Unit {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            dimension,
+            factor,
+            offset: 0.0,
+            exact: None,
+        }
+    }
+
+    fn with_offset(mut self, offset: f64) -> Unit {
+        self.offset = offset;
+        self
+    }
+
+    fn with_exact(mut self, factor: (i64, i64), offset: (i64, i64)) -> Unit {
+        let ratio = |(n, d): (i64, i64)| BigRational::new(n.into(), d.into());
+        self.exact = Some((ratio(factor), ratio(offset)));
+        self
+    }
+
+    pub fn to_base(&self, value: f64) -> f64 {
+        value * self.factor + self.offset
+    }
+
+    pub fn to_unit(&self, value: f64) -> f64 {
+        let difference = value - self.offset;
+        // Taking away the offset leaves rounding noise where the answer is
+        // zero, 5.7e-14 for 32 F in C, which would be shown as is.
+        if difference.abs() <= 1e-12 * self.offset.abs() {
+            return 0.0;
+        }
+        difference / self.factor
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.symbol == name || self.name == name || self.aliases.iter().any(|a| a == name)
+    }
+}
+
+pub struct Registry {
+    units: Vec<Unit>,
+    // Date of the exchange rates behind the currency units, if any are loaded.
+    rates_date: Option<String>,
+}
+
+impl Registry {
+    pub fn builtin() -> Registry {
+        use Dimension as D;
+
+        // 0 °F sits 459.67 Fahrenheit degrees of 5/9 K above absolute zero.
+        let ninths = 5.0 / 9.0;
+        let fahrenheit_zero = -Fahrenheit::ABSOLUTE_ZERO * ninths;
+
+        let units = vec![
+            Unit::new("meter", "m", &["metre"], D::LENGTH, 1.0),
+            Unit::new("kilometer", "km", &["kilometre"], D::LENGTH, 1000.0),
+            Unit::new("centimeter", "cm", &["centimetre"], D::LENGTH, 0.01),
+            Unit::new("millimeter", "mm", &["millimetre"], D::LENGTH, 0.001),
+            Unit::new("inch", "in", &["inches"], D::LENGTH, 0.0254),
+            Unit::new("foot", "ft", &["feet"], D::LENGTH, 0.3048),
+            Unit::new("yard", "yd", &[], D::LENGTH, 0.9144),
+            Unit::new("mile", "mi", &[], D::LENGTH, 1609.344),
+            Unit::new("kilogram", "kg", &[], D::MASS, 1.0),
+            Unit::new("gram", "g", &[], D::MASS, 0.001),
+            Unit::new("pound", "lb", &[], D::MASS, 0.453_592_37),
+            Unit::new("ounce", "oz", &[], D::MASS, 0.028_349_523_125),
+            Unit::new("second", "s", &["sec"], D::TIME, 1.0),
+            Unit::new("millisecond", "ms", &[], D::TIME, 0.001),
+            Unit::new("minute", "min", &[], D::TIME, 60.0),
+            Unit::new("hour", "h", &["hr"], D::TIME, 3600.0),
+            Unit::new("day", "d", &[], D::TIME, 86400.0),
+            Unit::new("mile per hour", "mph", &[], D::LENGTH / D::TIME, 0.44704),
+            Unit::new("kelvin", "K", &[], D::TEMPERATURE, 1.0),
+            Unit::new(
+                "celsius",
+                "C",
+                &["°C", "centigrade", "celcius"],
+                D::TEMPERATURE,
+                1.0,
+            )
+            .with_offset(CELSIUS_ZERO),
+            Unit::new(
+                "fahrenheit",
+                "F",
+                &["°F", "farenheit"],
+                D::TEMPERATURE,
+                ninths,
+            )
+            .with_offset(fahrenheit_zero)
+            .with_exact((5, 9), (45967, 180)),
+            Unit::new("rankine", "R", &["°R"], D::TEMPERATURE, ninths).with_exact((5, 9), (0, 1)),
+        ];
+        Registry {
+            units,
+            rates_date: None,
+        }
+    }
+
+    // Exact spelling wins; otherwise fall back to a case-insensitive match
+    // so that "Celsius" or "KM" still resolve, and to the singular of plurals.
+    // Single letters must be spelled exactly, "k" is not kelvin and "M" is
+    // not meter.
+    pub fn lookup(&self, name: &str) -> Option<&Unit> {
+        let find = |name: &str| {
+            let exact = self.units.iter().find(|u| u.matches(name));
+            if name.chars().count() < 2 {
+                return exact;
+            }
+            exact.or_else(|| {
+                let (lower, upper) = (name.to_lowercase(), name.to_uppercase());
+                self.units
+                    .iter()
+                    .find(|u| u.matches(&lower) || u.matches(&upper))
+            })
+        };
+        find(name).or_else(|| find(name.strip_suffix('s')?))
+    }
+
+    // Like lookup, but the error names the closest known unit when there is one.
+    pub fn find(&self, name: &str) -> Result<&Unit, String> {
+        self.lookup(name).ok_or_else(|| match self.suggest(name) {
+            Some(close) => format!("unknown unit '{name}', did you mean '{close}'?"),
+            None => format!("unknown unit '{name}'"),
+        })
+    }
+
+    // The symbol, name or alias closest to `name`, allowing one typo in
+    // short names and two in longer ones.
+    pub fn suggest(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        let allowed = if name.chars().count() <= 4 { 1 } else { 2 };
+        self.units
+            .iter()
+            .flat_map(|u| [&u.symbol, &u.name].into_iter().chain(&u.aliases))
+            .map(|candidate| (edit_distance(&name, &candidate.to_lowercase()), candidate))
+            .filter(|(distance, candidate)| {
+                *distance <= allowed && *distance < candidate.chars().count()
+            })
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, candidate)| candidate.as_str())
+    }
+
+    // Adds a unit, replacing one with the same symbol.
+    pub fn add(&mut self, unit: Unit) {
+        match self.units.iter_mut().find(|u| u.symbol == unit.symbol) {
+            Some(existing) => *existing = unit,
+            None => self.units.push(unit),
+        }
+    }
+
+    pub fn set_rates_date(&mut self, date: &str) {
+        self.rates_date = Some(date.to_string());
+    }
+
+    pub fn rates_date(&self) -> Option<&str> {
+        self.rates_date.as_deref()
+    }
+
+    pub fn units(&self) -> &[Unit] {
+        &self.units
+    }
+
+    // Fahrenheit readings are shown in Celsius and vice versa when no target
+    // unit is given, like the original menu did.
+    pub fn opposite(&self, unit: &Unit) -> Option<&Unit> {
+        match unit.symbol.as_str() {
+            "C" => self.lookup("F"),
+            "F" => self.lookup("C"),
+            _ => None,
+        }
+    }
+}
+
+// Levenshtein distance where swapping two neighbouring letters also counts
+// as one edit, the most common typo ("celsuis").
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    // rows[i][j] is the distance between the first i letters of a and j of b.
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+// Two decimals like the interactive mode, unless that would round the value away.
+pub fn format_number(value: f64) -> String {
+    if value == 0.0 || (0.01..1e12).contains(&value.abs()) {
+        format!("{value:.2}")
+    } else {
+        format!("{value:.4e}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("celsius", "celsius"), 0);
+        assert_eq!(edit_distance("celsuis", "celsius"), 1);
+        assert_eq!(edit_distance("kilomter", "kilometer"), 1);
+        assert_eq!(edit_distance("fahrenhiet", "fahrenheit"), 1);
+        assert_eq!(edit_distance("", "km"), 2);
+    }
+
+    #[test]
+    fn common_misspellings_resolve() {
+        let registry = Registry::builtin();
+        for (typo, symbol) in [
+            ("farenheit", "F"),
+            ("celcius", "C"),
+            ("Centigrade", "C"),
+            ("kilometre", "km"),
+            ("kilometres", "km"),
+        ] {
+            assert_eq!(
+                registry.lookup(typo).map(|u| u.symbol.as_str()),
+                Some(symbol)
+            );
+        }
+    }
+
+    #[test]
+    fn suggestions() {
+        let registry = Registry::builtin();
+        assert_eq!(
+            registry.find("kilomter").unwrap_err(),
+            "unknown unit 'kilomter', did you mean 'kilometer'?"
+        );
+        assert_eq!(registry.suggest("fahrenhiet"), Some("fahrenheit"));
+        assert_eq!(registry.suggest("Kelvn"), Some("kelvin"));
+        assert_eq!(registry.suggest("yrd"), Some("yd"));
+        assert_eq!(registry.suggest("parsec"), None);
+        assert_eq!(registry.find("x").unwrap_err(), "unknown unit 'x'");
+    }
+
+    #[test]
+    fn zero_on_offset_scales_is_exact() {
+        let registry = Registry::builtin();
+        let (f, c) = (registry.lookup("F").unwrap(), registry.lookup("C").unwrap());
+        assert_eq!(c.to_unit(f.to_base(32.0)), 0.0);
+        assert_eq!(format_number(c.to_unit(f.to_base(32.0))), "0.00");
+        // Small values that are really there stay.
+        assert!(c.to_unit(c.to_base(1e-6)) > 0.0);
+    }
+}
@@ -0,0 +1,235 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use libm::{exp, log, pow, sqrt};
+
+use crate::temperature::{Celsius, Fahrenheit};
+
+// A derived quantity along with warnings about inputs outside the range
+// its formula was fitted for. The value is still computed in that case.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate<T> {
+    pub value: T,
+    pub warnings: Vec<String>,
+}
+
+fn check_humidity(rh: f64) -> Result<(), String> {
+    if !(rh > 0.0 && rh <= 100.0) {
+        return Err(format!(
+            "relative humidity must be above 0% and at most 100%, got {rh}%"
+        ));
+    }
+    Ok(())
+}
+
+// Heat index of the NWS (Rothfusz regression with its two adjustments,
+// and Steadman's simple formula below 80 °F):
+// https://www.wpc.ncep.noaa.gov/html/heatindex_equation.shtml
+// Valid from 80 °F to 110 °F and 40% to 100% relative humidity, the range
+// of the NWS heat index chart.
+pub fn heat_index(t: impl Into<Fahrenheit>, rh: f64) -> Result<Estimate<Fahrenheit>, String> {
+    check_humidity(rh)?;
+    let t = t.into().value();
+    let mut warnings = Vec::new();
+    if !(80.0..=110.0).contains(&t) {
+        warnings.push(format!(
+            "heat index is defined from 80 °F to 110 °F (26.7 °C to 43.3 °C), got {t:.2} °F"
+        ));
+    }
+    if rh < 40.0 {
+        warnings.push(format!(
+            "heat index is defined from 40% relative humidity, got {rh}%"
+        ));
+    }
+
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    let hi = if (simple + t) / 2.0 < 80.0 {
+        simple
+    } else {
+        let mut hi = -42.379 + 2.049_015_23 * t + 10.143_331_27 * rh
+            - 0.224_755_41 * t * rh
+            - 0.006_837_83 * t * t
+            - 0.054_817_17 * rh * rh
+            + 0.001_228_74 * t * t * rh
+            + 0.000_852_82 * t * rh * rh
+            - 0.000_001_99 * t * t * rh * rh;
+        if rh < 13.0 && (80.0..=112.0).contains(&t) {
+            hi -= (13.0 - rh) / 4.0 * sqrt((17.0 - (t - 95.0).abs()) / 17.0);
+        } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+            hi += (rh - 85.0) / 10.0 * ((87.0 - t) / 5.0);
+        }
+        hi
+    };
+    Ok(Estimate {
+        value: Fahrenheit::new(hi)?,
+        warnings,
+    })
+}
+
+// Wind chill of the NWS (2001 formula), with the wind in mph at 10 m:
+// https://www.weather.gov/media/epz/wxcalc/windChill.pdf
+// Valid at or below 50 °F and for winds of 3 mph and more.
+pub fn wind_chill(t: impl Into<Fahrenheit>, wind_mph: f64) -> Result<Estimate<Fahrenheit>, String> {
+    if !(wind_mph >= 0.0 && wind_mph.is_finite()) {
+        return Err(format!(
+            "wind speed must not be negative, got {wind_mph} mph"
+        ));
+    }
+    let t = t.into().value();
+    let mut warnings = Vec::new();
+    if t > 50.0 {
+        warnings.push(format!(
+            "wind chill is defined at or below 50 °F (10 °C), got {t:.2} °F"
+        ));
+    }
+    if wind_mph < 3.0 {
+        warnings.push(format!(
+            "wind chill is defined from 3 mph (4.8 km/h), got {wind_mph:.2} mph"
+        ));
+    }
+
+    let v = pow(wind_mph, 0.16);
+    let wc = 35.74 + 0.6215 * t - 35.75 * v + 0.4275 * t * v;
+    Ok(Estimate {
+        value: Fahrenheit::new(wc)?,
+        warnings,
+    })
+}
+
+// Saturation vapour pressure in hPa, the Magnus formula with Bolton's
+// constants used by the NWS calculators:
+// https://www.weather.gov/media/epz/wxcalc/vaporPressure.pdf
+fn vapor_pressure(t: f64) -> f64 {
+    6.112 * exp(17.67 * t / (t + 243.5))
+}
+
+const MAGNUS_RANGE: (f64, f64) = (-30.0, 35.0);
+
+fn check_magnus(what: &str, t: f64, warnings: &mut Vec<String>) {
+    let (low, high) = MAGNUS_RANGE;
+    if !(low..=high).contains(&t) {
+        warnings.push(format!(
+            "{what} is accurate from {low} °C to {high} °C (-22 °F to 95 °F), got {t:.2} °C"
+        ));
+    }
+}
+
+// Dew point from the air temperature and relative humidity, inverting the
+// vapour pressure formula. Valid from -30 °C to 35 °C.
+pub fn dew_point(t: impl Into<Celsius>, rh: f64) -> Result<Estimate<Celsius>, String> {
+    check_humidity(rh)?;
+    let t = t.into().value();
+    let mut warnings = Vec::new();
+    check_magnus("dew point", t, &mut warnings);
+
+    let x = log(rh / 100.0 * vapor_pressure(t) / 6.112);
+    Ok(Estimate {
+        value: Celsius::new(243.5 * x / (17.67 - x))?,
+        warnings,
+    })
+}
+
+// Relative humidity in percent from the air temperature and dew point.
+// Valid from -30 °C to 35 °C.
+pub fn relative_humidity(
+    t: impl Into<Celsius>,
+    dew_point: impl Into<Celsius>,
+) -> Result<Estimate<f64>, String> {
+    let (t, td) = (t.into().value(), dew_point.into().value());
+    if td > t {
+        return Err(format!(
+            "the dew point ({td:.2} °C) cannot be above the air temperature ({t:.2} °C)"
+        ));
+    }
+    let mut warnings = Vec::new();
+    check_magnus("relative humidity", t, &mut warnings);
+    Ok(Estimate {
+        value: 100.0 * vapor_pressure(td) / vapor_pressure(t),
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temperature::Kelvin;
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() <= tolerance
+    }
+
+    // Spot checks against the NWS heat index chart, which rounds to whole degrees.
+    #[test]
+    fn heat_index_chart() {
+        for (t, rh, expected) in [
+            (80.0, 40.0, 80.0),
+            (90.0, 50.0, 95.0),
+            (96.0, 65.0, 121.0),
+            (100.0, 40.0, 109.0),
+            (110.0, 40.0, 136.0),
+            (84.0, 90.0, 98.0),
+        ] {
+            let hi = heat_index(Fahrenheit::new(t).unwrap(), rh).unwrap();
+            assert!(
+                close(hi.value.value(), expected, 1.0),
+                "{t} F {rh}%: {hi:?}"
+            );
+            assert!(hi.warnings.is_empty());
+        }
+    }
+
+    #[test]
+    fn heat_index_outside_range() {
+        let hi = heat_index(Celsius::new(20.0).unwrap(), 30.0).unwrap();
+        assert_eq!(hi.warnings.len(), 2);
+        assert!(close(hi.value.value(), 65.9, 0.1));
+        assert!(heat_index(Celsius::new(30.0).unwrap(), 120.0).is_err());
+    }
+
+    // Spot checks against the NWS wind chill chart.
+    #[test]
+    fn wind_chill_chart() {
+        for (t, wind, expected) in [
+            (40.0, 5.0, 36.0),
+            (0.0, 15.0, -19.0),
+            (-20.0, 30.0, -53.0),
+            (30.0, 60.0, 10.0),
+        ] {
+            let wc = wind_chill(Fahrenheit::new(t).unwrap(), wind).unwrap();
+            assert!(
+                close(wc.value.value(), expected, 0.5),
+                "{t} F {wind} mph: {wc:?}"
+            );
+            assert!(wc.warnings.is_empty());
+        }
+        let warm = wind_chill(Celsius::new(15.0).unwrap(), 2.0).unwrap();
+        assert_eq!(warm.warnings.len(), 2);
+        assert!(wind_chill(Celsius::new(0.0).unwrap(), -1.0).is_err());
+    }
+
+    #[test]
+    fn dew_point_and_humidity() {
+        let td = dew_point(Celsius::new(25.0).unwrap(), 60.0).unwrap();
+        assert!(close(td.value.value(), 16.7, 0.05), "{td:?}");
+        let rh = relative_humidity(Celsius::new(25.0).unwrap(), td.value).unwrap();
+        assert!(close(rh.value, 60.0, 1e-9));
+        // Any scale works, 77 °F is 25 °C.
+        let td_f = dew_point(Fahrenheit::new(77.0).unwrap(), 60.0).unwrap();
+        assert!(close(td_f.value.value(), td.value.value(), 1e-9));
+        let saturated = dew_point(Kelvin::new(283.15).unwrap(), 100.0).unwrap();
+        assert!(close(saturated.value.value(), 10.0, 1e-9));
+
+        assert_eq!(
+            dew_point(Celsius::new(40.0).unwrap(), 50.0)
+                .unwrap()
+                .warnings
+                .len(),
+            1
+        );
+        assert!(dew_point(Celsius::new(20.0).unwrap(), 0.0).is_err());
+        assert!(
+            relative_humidity(Celsius::new(10.0).unwrap(), Celsius::new(12.0).unwrap()).is_err()
+        );
+    }
+}
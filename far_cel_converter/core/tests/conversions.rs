@@ -0,0 +1,84 @@
+use far_cel_core::convert;
+use far_cel_core::temperature::{calc_celsius, calc_farenheit, Celsius, Fahrenheit};
+use proptest::prelude::*;
+
+const TEMPERATURES: &str = include_str!("fixtures/temperatures.csv");
+const UNITS: &str = include_str!("fixtures/units.csv");
+
+// Relative tolerance, the reference tables hold 12 decimals at most.
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.0)
+}
+
+// Data rows of a fixture, without comments and the header.
+fn rows(table: &str) -> impl Iterator<Item = Vec<&str>> {
+    table
+        .lines()
+        .filter(|l| !l.starts_with('#') && !l.starts_with(char::is_alphabetic))
+        .map(|l| l.split(',').collect())
+}
+
+#[test]
+fn temperature_reference_table() {
+    for row in rows(TEMPERATURES) {
+        let [f, c, k]: [f64; 3] = [0, 1, 2].map(|i| row[i].parse().unwrap());
+        let celsius = calc_celsius(Fahrenheit::new(f).unwrap()).value();
+        let fahrenheit = calc_farenheit(Celsius::new(c).unwrap()).value();
+        assert!(close(celsius, c), "{f} F gave {celsius} C, expected {c}");
+        assert!(
+            close(fahrenheit, f),
+            "{c} C gave {fahrenheit} F, expected {f}"
+        );
+        assert!(close(convert(f, "F", "K").unwrap(), k), "{f} F in K");
+        assert!(close(convert(k, "K", "C").unwrap(), c), "{k} K in C");
+    }
+}
+
+#[test]
+fn unit_reference_table() {
+    for row in rows(UNITS) {
+        let value: f64 = row[0].parse().unwrap();
+        let expected: f64 = row[3].parse().unwrap();
+        let result = convert(value, row[1], row[2]).unwrap();
+        assert!(
+            close(result, expected),
+            "{value} {} in {} gave {result}, expected {expected}",
+            row[1],
+            row[2]
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn fahrenheit_celsius_round_trip(f in Fahrenheit::ABSOLUTE_ZERO..1e7) {
+        let back = calc_farenheit(calc_celsius(Fahrenheit::new(f).unwrap())).value();
+        prop_assert!(close(back, f), "{} F came back as {}", f, back);
+    }
+
+    #[test]
+    fn celsius_fahrenheit_round_trip(c in Celsius::ABSOLUTE_ZERO..1e7) {
+        let back = calc_celsius(calc_farenheit(Celsius::new(c).unwrap())).value();
+        prop_assert!(close(back, c), "{} C came back as {}", c, back);
+    }
+
+    #[test]
+    fn conversions_keep_order(a in -459.67..1e6f64, b in -459.67..1e6f64) {
+        let (ca, cb) = (convert(a, "F", "C").unwrap(), convert(b, "F", "C").unwrap());
+        prop_assert!(a > b || ca <= cb, "{} F < {} F but {} C > {} C", a, b, ca, cb);
+    }
+
+    #[test]
+    fn below_absolute_zero_is_rejected(f in -1e7..Fahrenheit::ABSOLUTE_ZERO) {
+        prop_assert!(Fahrenheit::new(f).is_err());
+        prop_assert!(convert(f, "F", "C").is_err());
+    }
+
+    #[test]
+    fn length_round_trip(value in -1e9..1e9f64, from in 0..8usize, to in 0..8usize) {
+        let units = ["m", "km", "cm", "mm", "in", "ft", "yd", "mi"];
+        let there = convert(value, units[from], units[to]).unwrap();
+        let back = convert(there, units[to], units[from]).unwrap();
+        prop_assert!(close(back, value), "{} {} came back as {}", value, units[from], back);
+    }
+}
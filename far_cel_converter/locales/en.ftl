@@ -0,0 +1,66 @@
+# English messages, also the fallback for every other locale.
+# Terms starting with "-" describe how numbers are written.
+-decimal-separator = .
+-group-separator = ,
+
+welcome = Welcome to Farenheit/Celsius converter!
+    menu 1  Convert Farenheit to Celsius.
+    menu 2  Convert Celsius to Farenheit.
+    Or type an expression such as 3 ft + 20 in to cm, 'help' lists all commands.
+enter-fahrenheit = Enter temperature in Farenheit:
+enter-celsius = Enter temperature in Celsius:
+fahrenheit-is-celsius = { $from } Farenheit is equal to { $to } Celsius
+celsius-is-fahrenheit = { $from } Celsius is equal to { $to } Farenheit
+not-a-number = Given entry is not a number!
+invalid-option = Provide option menu 1 or menu 2 only!
+error = Error: { $message }
+warning = Warning: { $message }
+invalid-variable = Error: '{ $name }' cannot be used as a variable name
+interrupted = Type 'quit' or press Ctrl-D to exit.
+help = Commands:
+      menu 1, menu 2     the Farenheit/Celsius conversions from the menu
+      <expr> [to <unit>] evaluate an expression, e.g. 3 ft + 20 in to cm or (72F - 32F) in K
+                         write 10 C delta for a temperature difference rather than a reading
+      <name> = <expr>    store a result in a variable, 'ans' always holds the last one
+      heat-index <temp> <humidity>, wind-chill <temp> <wind>,
+      dew-point <temp> <humidity>, humidity <temp> <dew point>
+                         weather quantities, e.g. heat-index 32C 60% or wind-chill -10C, 30 km/h
+      vars               list the stored variables
+      units              list the known units
+      help               show this help
+      quit               leave the converter (Ctrl-D works too)
+
+# Command line help, { "" } stands for an empty line.
+usage = Usage:
+      far_cel_converter                              interactive mode
+      far_cel_converter <EXPR> [--to <UNIT>]         convert a value or expression, e.g. 98.6F --to C
+                                                     or "3 ft + 20 in to cm"
+      far_cel_converter --batch [--from <UNIT>] [--to <UNIT>]
+                                                     convert values read from stdin, one per line
+      far_cel_converter table <FROM> to <END> step <STEP> [--to <UNIT>] [--format <FORMAT>] [--chart <FILE>]
+                                                     print a conversion table, see table --help
+      far_cel_converter csv [<FILE>] --column <NAME> --from <UNIT> [--to <UNIT>] [--output <FILE>]
+                                                     convert columns of a CSV log, see csv --help
+      far_cel_converter heat-index|wind-chill|dew-point|humidity <TEMP> <INPUT>
+                                                     weather quantities, see heat-index --help
+      far_cel_converter rates [show | import <FILE>]  show or import currency rates, see rates --help
+      far_cel_converter --serve-stdio                answer line-delimited JSON-RPC 2.0 on stdin/stdout,
+                                                     methods convert, parse-expression and list-units
+    { "" }
+    Options:
+      --lang <LANG>      language of messages and numbers, e.g. pl; defaults to $LANG
+      --rates <FILE>     currency rate snapshot (JSON, CSV or ECB XML); defaults to $FAR_CEL_RATES
+                         or ~/.far_cel_converter_rates.json
+      --units <FILE>     TOML file of extra units; defaults to $FAR_CEL_UNITS
+                         or ~/.far_cel_converter_units.toml
+      --exact            convert with exact fractions instead of f64
+      --sig <N>          show N significant figures
+      --round <MODE>     half-even (default), half-up, half-down, up, down, ceiling or floor
+table-usage = Usage:
+      far_cel_converter table <FROM> to <END> step <STEP> [--to <UNIT>] [--format <FORMAT>] [--chart <FILE>]
+          e.g. table -40F to 212F step 10 --to C
+    { "" }
+    Options:
+      --to <UNIT>        unit of the second column, the other scale for F and C
+      --format <FORMAT>  text (default), csv, markdown or json
+      --chart <FILE>     also draw the conversion curve, as SVG for *.svg files, ASCII otherwise
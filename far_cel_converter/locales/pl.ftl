@@ -0,0 +1,67 @@
+# Polish messages. Decimals are written with a comma, as in 36,6.
+-decimal-separator = ,
+-group-separator = { " " }
+
+welcome = Witaj w przeliczniku Fahrenheit/Celsjusz!
+    menu 1  Przelicz stopnie Fahrenheita na Celsjusza.
+    menu 2  Przelicz stopnie Celsjusza na Fahrenheita.
+    Możesz też wpisać wyrażenie, np. 3 ft + 20 in to cm; 'help' wyświetla wszystkie polecenia.
+enter-fahrenheit = Podaj temperaturę w stopniach Fahrenheita:
+enter-celsius = Podaj temperaturę w stopniach Celsjusza:
+fahrenheit-is-celsius = { $from } °F to { $to } °C
+celsius-is-fahrenheit = { $from } °C to { $to } °F
+not-a-number = Podana wartość nie jest liczbą!
+invalid-option = Wybierz opcję menu 1 lub menu 2!
+error = Błąd: { $message }
+warning = Uwaga: { $message }
+invalid-variable = Błąd: '{ $name }' nie może być nazwą zmiennej
+interrupted = Wpisz 'quit' lub naciśnij Ctrl-D, aby zakończyć.
+help = Polecenia:
+      menu 1, menu 2     przeliczenia Fahrenheit/Celsjusz z menu
+      <wyrażenie> [to <jednostka>]
+                         oblicza wyrażenie, np. 3 ft + 20 in to cm lub (72F - 32F) in K
+                         10 C delta oznacza różnicę temperatur, a nie odczyt
+      <nazwa> = <wyrażenie>
+                         zapisuje wynik w zmiennej, 'ans' zawsze zawiera ostatni wynik
+      heat-index <temp> <wilgotność>, wind-chill <temp> <wiatr>,
+      dew-point <temp> <wilgotność>, humidity <temp> <punkt rosy>
+                         wielkości pogodowe, np. heat-index 32C, 60% lub wind-chill -10C, 30 km/h
+      vars               wyświetla zapisane zmienne
+      units              wyświetla znane jednostki
+      help               wyświetla tę pomoc
+      quit               kończy pracę (działa też Ctrl-D)
+
+# Command line help, { "" } stands for an empty line.
+usage = Użycie:
+      far_cel_converter                              tryb interaktywny
+      far_cel_converter <EXPR> [--to <UNIT>]         przelicza wartość lub wyrażenie, np. 98,6F --to C
+                                                     lub "3 ft + 20 in to cm"
+      far_cel_converter --batch [--from <UNIT>] [--to <UNIT>]
+                                                     przelicza wartości ze stdin, po jednej w wierszu
+      far_cel_converter table <FROM> to <END> step <STEP> [--to <UNIT>] [--format <FORMAT>] [--chart <FILE>]
+                                                     wypisuje tabelę przeliczeń, zobacz table --help
+      far_cel_converter csv [<FILE>] --column <NAME> --from <UNIT> [--to <UNIT>] [--output <FILE>]
+                                                     przelicza kolumny dziennika CSV, zobacz csv --help
+      far_cel_converter heat-index|wind-chill|dew-point|humidity <TEMP> <INPUT>
+                                                     wielkości pogodowe, zobacz heat-index --help
+      far_cel_converter rates [show | import <FILE>]  pokazuje lub importuje kursy walut, zobacz rates --help
+      far_cel_converter --serve-stdio                odpowiada na JSON-RPC 2.0 w wierszach stdin/stdout,
+                                                     metody convert, parse-expression i list-units
+    { "" }
+    Opcje:
+      --lang <LANG>      język komunikatów i liczb, np. pl; domyślnie $LANG
+      --rates <FILE>     plik z kursami walut (JSON, CSV lub XML EBC); domyślnie $FAR_CEL_RATES
+                         lub ~/.far_cel_converter_rates.json
+      --units <FILE>     plik TOML z dodatkowymi jednostkami; domyślnie $FAR_CEL_UNITS
+                         lub ~/.far_cel_converter_units.toml
+      --exact            przelicza dokładnie na ułamkach zamiast na f64
+      --sig <N>          pokazuje N cyfr znaczących
+      --round <MODE>     half-even (domyślnie), half-up, half-down, up, down, ceiling lub floor
+table-usage = Użycie:
+      far_cel_converter table <FROM> to <END> step <STEP> [--to <UNIT>] [--format <FORMAT>] [--chart <FILE>]
+          np. table -40F to 212F step 10 --to C
+    { "" }
+    Opcje:
+      --to <UNIT>        jednostka drugiej kolumny, dla F i C domyślnie druga skala
+      --format <FORMAT>  text (domyślnie), csv, markdown lub json
+      --chart <FILE>     rysuje też krzywą przeliczenia, jako SVG dla plików *.svg, inaczej w ASCII
@@ -0,0 +1,218 @@
+use far_cel_core::units::format_number;
+
+use crate::i18n::Locale;
+use crate::table::Table;
+
+const ASCII_WIDTH: usize = 60;
+const ASCII_HEIGHT: usize = 20;
+const SVG_WIDTH: f64 = 640.0;
+const SVG_HEIGHT: f64 = 400.0;
+const SVG_MARGIN: f64 = 60.0;
+
+// Smallest and largest value of a column, widened when they coincide so a
+// one-row table does not divide by zero.
+fn bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+        (lo.min(v), hi.max(v))
+    });
+    if min == max {
+        (min - 1.0, max + 1.0)
+    } else {
+        (min, max)
+    }
+}
+
+// Reads the converted value at `x` off the straight segments between rows.
+fn interpolate(rows: &[(f64, f64)], x: f64) -> f64 {
+    for pair in rows.windows(2) {
+        let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+        if x <= x1 {
+            return y0 + (y1 - y0) * (x - x0) / (x1 - x0);
+        }
+    }
+    rows.last().map(|r| r.1).unwrap_or(0.0)
+}
+
+// Labels use the locale's decimal separator, like the table.
+pub fn ascii(table: &Table, locale: &Locale) -> String {
+    let (x_min, x_max) = bounds(table.rows.iter().map(|r| r.0));
+    let (y_min, y_max) = bounds(table.rows.iter().map(|r| r.1));
+    let heights: Vec<usize> = (0..ASCII_WIDTH)
+        .map(|col| {
+            let x = x_min + (x_max - x_min) * col as f64 / (ASCII_WIDTH - 1) as f64;
+            let y = if table.rows.len() > 1 {
+                interpolate(&table.rows, x)
+            } else {
+                table.rows[0].1
+            };
+            let row = ((y_max - y) / (y_max - y_min) * (ASCII_HEIGHT - 1) as f64).round();
+            (row as usize).min(ASCII_HEIGHT - 1)
+        })
+        .collect();
+
+    let top = format_number(y_max);
+    let bottom = format_number(y_min);
+    let label_width = top.len().max(bottom.len()).max(table.to.symbol.len());
+    let mut out = format!("{:>label_width$}\n", table.to.symbol);
+    for i in 0..ASCII_HEIGHT {
+        let label = match i {
+            0 => top.as_str(),
+            i if i == ASCII_HEIGHT - 1 => bottom.as_str(),
+            _ => "",
+        };
+        let line: String = heights
+            .iter()
+            .map(|&h| if h == i { '*' } else { ' ' })
+            .collect();
+        out += &format!("{label:>label_width$} |{}\n", line.trim_end());
+    }
+    out += &format!("{:>label_width$} +{}\n", "", "-".repeat(ASCII_WIDTH));
+    let left = format_number(x_min);
+    let right = format_number(x_max);
+    let gap = (ASCII_WIDTH + 1).saturating_sub(left.len() + right.len());
+    out += &format!(
+        "{:>label_width$}  {left}{}{right} {}\n",
+        "",
+        " ".repeat(gap),
+        table.from.symbol
+    );
+    locale.localize(&out)
+}
+
+pub fn svg(table: &Table, locale: &Locale) -> String {
+    let (x_min, x_max) = bounds(table.rows.iter().map(|r| r.0));
+    let (y_min, y_max) = bounds(table.rows.iter().map(|r| r.1));
+    let (left, right) = (SVG_MARGIN, SVG_WIDTH - SVG_MARGIN / 2.0);
+    let (top, bottom) = (SVG_MARGIN / 2.0, SVG_HEIGHT - SVG_MARGIN);
+    let px = |x: f64| left + (x - x_min) / (x_max - x_min) * (right - left);
+    let py = |y: f64| bottom - (y - y_min) / (y_max - y_min) * (bottom - top);
+
+    let points: Vec<String> = table
+        .rows
+        .iter()
+        .map(|&(x, y)| format!("{:.1},{:.1}", px(x), py(y)))
+        .collect();
+
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{SVG_WIDTH}\" height=\"{SVG_HEIGHT}\" font-family=\"sans-serif\" font-size=\"12\">\n"
+    );
+    out += "  <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n";
+    out += &format!(
+        "  <polyline points=\"{left},{top} {left},{bottom} {right},{bottom}\" fill=\"none\" stroke=\"black\"/>\n"
+    );
+    out += &format!(
+        "  <polyline points=\"{}\" fill=\"none\" stroke=\"steelblue\" stroke-width=\"2\"/>\n",
+        points.join(" ")
+    );
+    for (text, x, y, anchor) in [
+        (
+            locale.localize(&format_number(x_min)),
+            left,
+            bottom + 18.0,
+            "start",
+        ),
+        (
+            locale.localize(&format_number(x_max)),
+            right,
+            bottom + 18.0,
+            "end",
+        ),
+        (
+            locale.localize(&format_number(y_min)),
+            left - 6.0,
+            bottom,
+            "end",
+        ),
+        (
+            locale.localize(&format_number(y_max)),
+            left - 6.0,
+            top + 4.0,
+            "end",
+        ),
+        (
+            xml_escape(&table.from.symbol),
+            (left + right) / 2.0,
+            bottom + 36.0,
+            "middle",
+        ),
+        (
+            xml_escape(&table.to.symbol),
+            left - 6.0,
+            (top + bottom) / 2.0,
+            "end",
+        ),
+    ] {
+        out +=
+            &format!("  <text x=\"{x:.1}\" y=\"{y:.1}\" text-anchor=\"{anchor}\">{text}</text>\n");
+    }
+    out += "</svg>\n";
+    out
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use far_cel_core::units::Registry;
+
+    fn table(spec: &str) -> Table {
+        Table::build(spec, None, &Registry::builtin()).unwrap()
+    }
+
+    #[test]
+    fn ascii_chart() {
+        let chart = ascii(&table("0C to 100C step 50"), &Locale::new("en"));
+        let lines: Vec<&str> = chart.lines().collect();
+        assert_eq!(lines.len(), ASCII_HEIGHT + 3);
+        assert_eq!(lines[0], "     F");
+        assert!(lines[1].starts_with("212.00 |"));
+        assert!(lines[ASCII_HEIGHT].starts_with(" 32.00 |*"));
+        assert_eq!(
+            lines[ASCII_HEIGHT + 1],
+            format!("       +{}", "-".repeat(60))
+        );
+        assert!(lines[ASCII_HEIGHT + 2].ends_with("100.00 C"));
+        // One point per column on a straight line from bottom left to top right.
+        assert_eq!(chart.matches('*').count(), ASCII_WIDTH);
+        assert!(lines[1].ends_with('*'));
+    }
+
+    #[test]
+    fn svg_chart() {
+        let chart = svg(&table("0C to 100C step 50"), &Locale::new("en"));
+        assert!(chart.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(chart.ends_with("</svg>\n"));
+        assert!(chart.contains("points=\"60.0,340.0 335.0,185.0 610.0,30.0\""));
+        for label in [">0.00<", ">100.00<", ">32.00<", ">212.00<", ">C<", ">F<"] {
+            assert!(chart.contains(label), "{label}");
+        }
+    }
+
+    #[test]
+    fn one_row_does_not_divide_by_zero() {
+        let one = table("0C to 0C step 1");
+        assert!(!svg(&one, &Locale::new("en")).contains("NaN"));
+        assert_eq!(
+            ascii(&one, &Locale::new("en")).matches('*').count(),
+            ASCII_WIDTH
+        );
+        assert_eq!(xml_escape("a<b>&c"), "a&lt;b&gt;&amp;c");
+    }
+
+    #[test]
+    fn labels_use_the_locale() {
+        let pl = Locale::new("pl");
+        let chart = ascii(&table("0C to 100C step 50"), &pl);
+        assert!(chart.contains("212,00 |"));
+        assert!(chart.ends_with("100,00 C\n"));
+        let chart = svg(&table("0C to 100C step 50"), &pl);
+        assert!(chart.contains(">212,00<"));
+        // Coordinates keep the dot SVG needs.
+        assert!(chart.contains("points=\"60.0,340.0 335.0,185.0 610.0,30.0\""));
+    }
+}
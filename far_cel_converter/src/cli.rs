@@ -0,0 +1,192 @@
+use std::io::{self, BufRead, Write};
+
+use far_cel_core::exact::{self, Rounding};
+use far_cel_core::expr::{self, Quantity, Variables};
+use far_cel_core::units::{Dimension, Registry, Unit};
+
+use crate::currency;
+use crate::i18n::Locale;
+use crate::meteorology;
+use crate::sensor_log;
+use crate::server;
+use crate::table;
+
+struct Options {
+    // Positional words joined back together, so the expression may be quoted or not.
+    value: Vec<String>,
+    from: Option<Unit>,
+    to: Option<Unit>,
+    batch: bool,
+    exact: bool,
+    sig: Option<u32>,
+    rounding: Rounding,
+}
+
+fn parse_args(args: &[String], registry: &Registry) -> Result<Options, String> {
+    let mut opts = Options {
+        value: Vec::new(),
+        from: None,
+        to: None,
+        batch: false,
+        exact: false,
+        sig: None,
+        rounding: Rounding::HalfEven,
+    };
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            // Everything after -- is the value, even if it looks like an option.
+            "--" => opts.value.extend(args.by_ref().cloned()),
+            "--batch" => opts.batch = true,
+            "--exact" => opts.exact = true,
+            "--sig" => {
+                let sig = args.next().ok_or("--sig needs a number")?;
+                opts.sig = match sig.parse() {
+                    Ok(0) | Err(_) => return Err(format!("invalid significant figures '{sig}'")),
+                    Ok(sig) => Some(sig),
+                };
+            }
+            "--round" => {
+                let mode = args.next().ok_or("--round needs a mode")?;
+                opts.rounding = Rounding::from_name(mode)
+                    .ok_or_else(|| format!("unknown rounding mode '{mode}'"))?;
+            }
+            "--from" | "--to" => {
+                let name = args.next().ok_or_else(|| format!("{arg} needs a unit"))?;
+                let unit = registry.find(name)?.clone();
+                if arg == "--from" {
+                    opts.from = Some(unit);
+                } else {
+                    opts.to = Some(unit);
+                }
+            }
+            _ if !arg.starts_with("--") => opts.value.push(arg.clone()),
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+
+    if opts.batch && !opts.value.is_empty() {
+        return Err("--batch reads values from stdin, do not pass one".to_string());
+    }
+    if !opts.batch && opts.value.is_empty() {
+        return Err("no value given".to_string());
+    }
+    Ok(opts)
+}
+
+fn convert_entry(entry: &str, opts: &Options, registry: &Registry) -> Result<String, String> {
+    if opts.exact {
+        let (value, unit) =
+            exact::convert_entry(entry, opts.from.as_ref(), opts.to.as_ref(), registry)?;
+        let text = exact::describe(&value, &unit, opts.sig, opts.rounding);
+        return Ok(currency::annotate(text, unit.dimension, registry));
+    }
+
+    let (mut quantity, target) = expr::evaluate(entry, registry, &Variables::new())?;
+    if quantity.dimension == Dimension::NONE {
+        if let Some(from) = &opts.from {
+            quantity = Quantity::of(quantity.value, from).checked()?;
+        }
+    }
+
+    let target = target
+        .or_else(|| opts.to.clone())
+        .or_else(|| registry.opposite(quantity.unit.as_ref()?).cloned());
+    if let Some(unit) = target {
+        quantity = quantity.convert_to(&unit)?;
+    }
+    let text = match opts.sig {
+        Some(sig) => show_sig(&quantity, sig, opts.rounding)?,
+        None => quantity.to_string(),
+    };
+    Ok(currency::annotate(text, quantity.dimension, registry))
+}
+
+fn show_sig(quantity: &Quantity, sig: u32, rounding: Rounding) -> Result<String, String> {
+    let number = exact::format_sig(&exact::from_f64(quantity.display_value())?, sig, rounding);
+    Ok(match quantity.label() {
+        Some(label) => format!("{number} {label}"),
+        None => number,
+    })
+}
+
+fn run_batch(opts: &Options, registry: &Registry, locale: &Locale) -> i32 {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut failed = false;
+
+    for (n, line) in io::stdin().lock().lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            // A line that is not UTF-8 is skipped like any bad entry, a
+            // failing read would fail again, so it ends the batch.
+            Err(e) => {
+                eprintln!("line {}: cannot read it: {e}", n + 1);
+                failed = true;
+                if e.kind() == io::ErrorKind::InvalidData {
+                    continue;
+                }
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match convert_entry(&locale.delocalize(&line), opts, registry) {
+            Ok(result) => writeln!(out, "{}", locale.localize(&result)).expect("Failed to write!"),
+            Err(e) => {
+                eprintln!("line {}: {e}", n + 1);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        1
+    } else {
+        0
+    }
+}
+
+// Runs the converter without prompts and returns the process exit code.
+pub fn run(args: &[String], locale: &Locale, registry: &Registry) -> i32 {
+    match args[0].as_str() {
+        "table" => return table::run(&args[1..], registry, locale),
+        "csv" => return sensor_log::run(&args[1..], registry),
+        "--serve-stdio" => return server::run(registry),
+        command if meteorology::COMMANDS.contains(&command) => {
+            return meteorology::run(command, &args[1..], registry, locale)
+        }
+        _ => {}
+    }
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{}", locale.text("usage"));
+        return 0;
+    }
+
+    let opts = match parse_args(args, registry) {
+        Ok(opts) => opts,
+        Err(e) => {
+            let message = format!("{e}\n{}", locale.text("usage"));
+            eprintln!("{}", locale.format("error", &[("message", &message)]));
+            return 2;
+        }
+    };
+
+    if opts.batch {
+        return run_batch(&opts, registry, locale);
+    }
+
+    let entry = locale.delocalize(&opts.value.join(" "));
+    match convert_entry(&entry, &opts, registry) {
+        Ok(result) => {
+            println!("{}", locale.localize(&result));
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", locale.format("error", &[("message", &e)]));
+            1
+        }
+    }
+}
@@ -0,0 +1,395 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use far_cel_core::exact;
+use far_cel_core::units::{Dimension, Registry, Unit};
+use num_rational::BigRational;
+use num_traits::{One, Zero};
+use serde::{Deserialize, Serialize};
+
+const USAGE: &str = "Usage:
+  far_cel_converter rates [show]            list the rates of the current snapshot
+  far_cel_converter rates import <FILE>     save the rates of an ECB XML, JSON or CSV file as the snapshot
+
+The snapshot is the file given with --rates, else $FAR_CEL_RATES,
+else ~/.far_cel_converter_rates.json. A *.csv path is written as CSV.";
+
+// Currencies published by the ECB, so `list-units` can show more than codes.
+const NAMES: [(&str, &str); 31] = [
+    ("EUR", "euro"),
+    ("USD", "US dollar"),
+    ("JPY", "Japanese yen"),
+    ("BGN", "Bulgarian lev"),
+    ("CZK", "Czech koruna"),
+    ("DKK", "Danish krone"),
+    ("GBP", "pound sterling"),
+    ("HUF", "Hungarian forint"),
+    ("PLN", "Polish zloty"),
+    ("RON", "Romanian leu"),
+    ("SEK", "Swedish krona"),
+    ("CHF", "Swiss franc"),
+    ("ISK", "Icelandic krona"),
+    ("NOK", "Norwegian krone"),
+    ("TRY", "Turkish lira"),
+    ("AUD", "Australian dollar"),
+    ("BRL", "Brazilian real"),
+    ("CAD", "Canadian dollar"),
+    ("CNY", "Chinese yuan renminbi"),
+    ("HKD", "Hong Kong dollar"),
+    ("IDR", "Indonesian rupiah"),
+    ("ILS", "Israeli shekel"),
+    ("INR", "Indian rupee"),
+    ("KRW", "South Korean won"),
+    ("MXN", "Mexican peso"),
+    ("MYR", "Malaysian ringgit"),
+    ("NZD", "New Zealand dollar"),
+    ("PHP", "Philippine peso"),
+    ("SGD", "Singapore dollar"),
+    ("THB", "Thai baht"),
+    ("ZAR", "South African rand"),
+];
+
+fn default_base() -> String {
+    "EUR".to_string()
+}
+
+// Exchange rates of one day: how many units of each currency one unit of
+// `base` buys, the way the ECB quotes them against the euro.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub date: String,
+    #[serde(default = "default_base")]
+    pub base: String,
+    pub rates: BTreeMap<String, f64>,
+}
+
+fn valid_code(code: &str) -> bool {
+    code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase())
+}
+
+// Dates are kept as ISO 8601 text, which also sorts them.
+fn valid_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    parts.len() == 3
+        && [4, 2, 2]
+            .iter()
+            .zip(&parts)
+            .all(|(&len, p)| p.len() == len && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+impl Snapshot {
+    fn checked(self) -> Result<Snapshot, String> {
+        if !valid_date(&self.date) {
+            return Err(format!("invalid rate date '{}'", self.date));
+        }
+        if !valid_code(&self.base) {
+            return Err(format!("invalid currency code '{}'", self.base));
+        }
+        if self.rates.is_empty() {
+            return Err("the snapshot has no rates".to_string());
+        }
+        for (code, rate) in &self.rates {
+            if !valid_code(code) {
+                return Err(format!("invalid currency code '{code}'"));
+            }
+            if !rate.is_finite() || *rate <= 0.0 {
+                return Err(format!("invalid rate {rate} for {code}"));
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn from_json(text: &str) -> Result<Snapshot, String> {
+        let snapshot: Snapshot = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        snapshot.checked()
+    }
+
+    // Rows of `date,currency,rate` against the euro. A file may hold several
+    // days; only the latest one is used.
+    pub fn from_csv(text: &str) -> Result<Snapshot, String> {
+        let mut rows = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("date,") {
+                continue;
+            }
+            let cells: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [date, code, rate] = cells[..] else {
+                return Err(format!("line {}: expected date,currency,rate", n + 1));
+            };
+            let rate: f64 = rate
+                .parse()
+                .map_err(|_| format!("line {}: rate '{rate}' is not a number", n + 1))?;
+            rows.push((date.to_string(), code.to_string(), rate));
+        }
+        let date = rows
+            .iter()
+            .map(|(date, _, _)| date.clone())
+            .max()
+            .ok_or("the file has no rates")?;
+        let rates = rows
+            .into_iter()
+            .filter(|(d, _, _)| *d == date)
+            .map(|(_, code, rate)| (code, rate))
+            .collect();
+        Snapshot {
+            date,
+            base: default_base(),
+            rates,
+        }
+        .checked()
+    }
+
+    // The ECB reference rate files, eurofxref-daily.xml or the -hist ones:
+    // <Cube time="..."> elements, each holding <Cube currency="..." rate="..."/>.
+    pub fn from_ecb_xml(text: &str) -> Result<Snapshot, String> {
+        let doc = roxmltree::Document::parse(text).map_err(|e| e.to_string())?;
+        let day = doc
+            .descendants()
+            .filter(|n| n.has_tag_name("Cube") && n.has_attribute("time"))
+            .max_by_key(|n| n.attribute("time"))
+            .ok_or("no <Cube time=\"...\"> element, is this an ECB rate file?")?;
+
+        let mut rates = BTreeMap::new();
+        for cube in day.children().filter(|n| n.has_tag_name("Cube")) {
+            let (Some(code), Some(rate)) = (cube.attribute("currency"), cube.attribute("rate"))
+            else {
+                continue;
+            };
+            let rate: f64 = rate
+                .parse()
+                .map_err(|_| format!("rate '{rate}' of {code} is not a number"))?;
+            rates.insert(code.to_string(), rate);
+        }
+        Snapshot {
+            date: day.attribute("time").unwrap_or_default().to_string(),
+            base: default_base(),
+            rates,
+        }
+        .checked()
+    }
+
+    // Picks the format from the file extension.
+    pub fn load(path: &Path) -> Result<Snapshot, String> {
+        let text =
+            fs::read_to_string(path).map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+        let snapshot = match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => Snapshot::from_csv(&text),
+            Some("xml") => Snapshot::from_ecb_xml(&text),
+            _ => Snapshot::from_json(&text),
+        };
+        snapshot.map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = if path.extension().is_some_and(|e| e == "csv") {
+            if self.base != "EUR" {
+                return Err("CSV snapshots hold rates against EUR only".to_string());
+            }
+            let mut out = "date,currency,rate\n".to_string();
+            for (code, rate) in &self.rates {
+                out += &format!("{},{code},{rate}\n", self.date);
+            }
+            out
+        } else {
+            serde_json::to_string_pretty(self).map_err(|e| e.to_string())? + "\n"
+        };
+        fs::write(path, text).map_err(|e| format!("cannot write {}: {e}", path.display()))
+    }
+
+    // Registers one unit per currency, measured in the base currency.
+    pub fn install(&self, registry: &mut Registry) {
+        let base = (self.base.clone(), 1.0);
+        for (code, rate) in self
+            .rates
+            .iter()
+            .map(|(c, r)| (c.clone(), *r))
+            .chain([base])
+        {
+            let name = NAMES
+                .iter()
+                .find(|(c, _)| *c == code)
+                .map_or(code.as_str(), |(_, name)| name);
+            let mut unit = Unit::new(name, &code, &[], Dimension::CURRENCY, 1.0 / rate);
+            // The quoted decimal rate is exact, its reciprocal in f64 is not.
+            // Validated rates are always finite.
+            unit.exact = exact::from_f64(rate)
+                .ok()
+                .map(|rate| (BigRational::one() / rate, BigRational::zero()));
+            registry.add(unit);
+        }
+        registry.set_rates_date(&self.date);
+    }
+}
+
+// The snapshot named with --rates, else $FAR_CEL_RATES, else the one in the
+// home directory. The flag and variable must name a file; the default may be
+// missing, it only exists after `rates import`.
+fn snapshot_path(explicit: Option<&str>) -> Option<(PathBuf, bool)> {
+    if let Some(path) = explicit {
+        return Some((PathBuf::from(path), true));
+    }
+    if let Some(path) = env::var_os("FAR_CEL_RATES") {
+        return Some((PathBuf::from(path), true));
+    }
+    let home = env::var_os("HOME")?;
+    Some((
+        PathBuf::from(home).join(".far_cel_converter_rates.json"),
+        false,
+    ))
+}
+
+// Adds the currencies of the rate snapshot, when there is one, to `registry`.
+pub fn load(registry: &mut Registry, explicit: Option<&str>) -> Result<(), String> {
+    let Some((path, required)) = snapshot_path(explicit) else {
+        return Ok(());
+    };
+    if !required && !path.exists() {
+        return Ok(());
+    }
+    Snapshot::load(&path)?.install(registry);
+    Ok(())
+}
+
+// Appends the date of the rates to results involving money.
+pub fn annotate(text: String, dimension: Dimension, registry: &Registry) -> String {
+    match registry.rates_date() {
+        Some(date) if dimension.involves(Dimension::CURRENCY) => {
+            format!("{text} (rates of {date})")
+        }
+        _ => text,
+    }
+}
+
+// Runs `far_cel_converter rates ...` and returns the process exit code.
+pub fn run(args: &[String], explicit: Option<&str>) -> i32 {
+    let Some((path, _)) = snapshot_path(explicit) else {
+        eprintln!("Error: no snapshot file, pass --rates <FILE>");
+        return 2;
+    };
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args[..] {
+        [] | ["show"] => match Snapshot::load(&path) {
+            Ok(snapshot) => {
+                println!("Rates of {} for 1 {}:", snapshot.date, snapshot.base);
+                for (code, rate) in &snapshot.rates {
+                    println!("  {code} {rate}");
+                }
+                0
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                1
+            }
+        },
+        ["import", file] => {
+            let saved = Snapshot::load(Path::new(file)).and_then(|s| {
+                s.save(&path)?;
+                Ok(s)
+            });
+            match saved {
+                Ok(snapshot) => {
+                    println!(
+                        "Saved {} rates of {} to {}",
+                        snapshot.rates.len(),
+                        snapshot.date,
+                        path.display()
+                    );
+                    0
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    1
+                }
+            }
+        }
+        ["--help"] | ["-h"] => {
+            println!("{USAGE}");
+            0
+        }
+        _ => {
+            eprintln!("Error: unexpected arguments\n{USAGE}");
+            2
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use far_cel_core::expr::{self, Variables};
+
+    const ECB_DAILY: &str = include_str!("../tests/fixtures/eurofxref-daily.xml");
+    const ECB_HIST: &str = include_str!("../tests/fixtures/eurofxref-hist.xml");
+    const RATES_JSON: &str = include_str!("../tests/fixtures/rates.json");
+    const RATES_CSV: &str = include_str!("../tests/fixtures/rates.csv");
+
+    fn convert(snapshot: &Snapshot, input: &str) -> String {
+        let mut registry = Registry::builtin();
+        snapshot.install(&mut registry);
+        let (quantity, target) = expr::evaluate(input, &registry, &Variables::new()).unwrap();
+        let quantity = quantity.convert_to(&target.unwrap()).unwrap();
+        annotate(quantity.to_string(), quantity.dimension, &registry)
+    }
+
+    #[test]
+    fn ecb_daily_file() {
+        let snapshot = Snapshot::from_ecb_xml(ECB_DAILY).unwrap();
+        assert_eq!(snapshot.date, "2024-01-05");
+        assert_eq!(snapshot.base, "EUR");
+        assert_eq!(snapshot.rates.len(), 30);
+        assert_eq!(snapshot.rates["USD"], 1.0921);
+        assert_eq!(
+            convert(&snapshot, "100 EUR to USD"),
+            "109.21 USD (rates of 2024-01-05)"
+        );
+    }
+
+    #[test]
+    fn ecb_history_uses_latest_day() {
+        let snapshot = Snapshot::from_ecb_xml(ECB_HIST).unwrap();
+        assert_eq!(snapshot.date, "2024-01-05");
+        assert_eq!(snapshot.rates["USD"], 1.0921);
+    }
+
+    #[test]
+    fn json_and_csv_snapshots() {
+        let json = Snapshot::from_json(RATES_JSON).unwrap();
+        let csv = Snapshot::from_csv(RATES_CSV).unwrap();
+        assert_eq!(json, csv);
+        assert_eq!(
+            convert(&json, "10 USD in PLN"),
+            "39.93 PLN (rates of 2024-01-05)"
+        );
+        assert_eq!(convert(&json, "2 m to cm"), "200.00 cm");
+    }
+
+    #[test]
+    fn exact_rates() {
+        let mut registry = Registry::builtin();
+        Snapshot::from_json(RATES_JSON)
+            .unwrap()
+            .install(&mut registry);
+        let (value, unit) =
+            exact::convert_entry("109.21 USD to EUR", None, None, &registry).unwrap();
+        assert_eq!(unit.symbol, "EUR");
+        assert_eq!(value, BigRational::from_integer(100.into()));
+    }
+
+    #[test]
+    fn rejects_bad_snapshots() {
+        for json in [
+            r#"{"date": "yesterday", "rates": {"USD": 1.1}}"#,
+            r#"{"date": "2024-01-05", "rates": {"USD": -1.1}}"#,
+            r#"{"date": "2024-01-05", "rates": {"dollar": 1.1}}"#,
+            r#"{"date": "2024-01-05", "rates": {}}"#,
+        ] {
+            assert!(Snapshot::from_json(json).is_err(), "{json}");
+        }
+        assert!(Snapshot::from_csv("2024-01-05,USD,lots").is_err());
+        assert!(Snapshot::from_ecb_xml("<rates/>").is_err());
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::env;
+
+// Message catalogs in a subset of Fluent: `key = value` lines, values
+// continued on lines indented by four spaces, `{ $name }` arguments and
+// `{ " " }` string literals. English is the fallback for missing keys.
+const CATALOGS: [(&str, &str); 2] = [
+    ("en", include_str!("../locales/en.ftl")),
+    ("pl", include_str!("../locales/pl.ftl")),
+];
+const CONTINUATION_INDENT: usize = 4;
+
+fn parse_catalog(source: &str) -> HashMap<String, String> {
+    let mut messages = HashMap::new();
+    let mut current: Option<(String, String)> = None;
+
+    for line in source.lines() {
+        if let Some((_, value)) = current.as_mut().filter(|_| line.starts_with(' ')) {
+            let indent = line.len() - line.trim_start().len();
+            value.push('\n');
+            value.push_str(&line[indent.min(CONTINUATION_INDENT)..]);
+            continue;
+        }
+        if let Some((key, value)) = current.take() {
+            messages.insert(key, value);
+        }
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            current = Some((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    if let Some((key, value)) = current {
+        messages.insert(key, value);
+    }
+
+    // String literals like { " " } keep whitespace Fluent would otherwise trim.
+    for value in messages.values_mut() {
+        while let Some(start) = value.find("{ \"") {
+            let Some(len) = value[start + 3..].find("\" }") else {
+                break;
+            };
+            let literal = value[start + 3..start + 3 + len].to_string();
+            value.replace_range(start..start + len + 6, &literal);
+        }
+    }
+    messages
+}
+
+fn catalog(tag: &str) -> Option<HashMap<String, String>> {
+    CATALOGS
+        .iter()
+        .find(|(t, _)| *t == tag)
+        .map(|(_, source)| parse_catalog(source))
+}
+
+pub struct Locale {
+    messages: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+    decimal: char,
+    group: char,
+}
+
+impl Locale {
+    // Takes a language such as "pl", "pl_PL.UTF-8" or "pl-PL"; anything
+    // without a catalog falls back to English.
+    pub fn new(lang: &str) -> Locale {
+        let tag = lang
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let fallback = catalog("en").unwrap_or_default();
+        let messages = catalog(&tag).unwrap_or_else(|| fallback.clone());
+        let separator = |key: &str, default: char| {
+            messages
+                .get(key)
+                .and_then(|s| s.chars().next())
+                .unwrap_or(default)
+        };
+        Locale {
+            decimal: separator("-decimal-separator", '.'),
+            group: separator("-group-separator", ','),
+            messages,
+            fallback,
+        }
+    }
+
+    // Uses --lang when given, otherwise the usual LC_ALL, LC_MESSAGES, LANG order.
+    pub fn select(lang: Option<&str>) -> Locale {
+        let from_env = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty());
+        Locale::new(
+            lang.map(str::to_string)
+                .or(from_env)
+                .as_deref()
+                .unwrap_or("en"),
+        )
+    }
+
+    pub fn text(&self, key: &str) -> String {
+        self.format(key, &[])
+    }
+
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> String {
+        let mut text = self
+            .messages
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .cloned()
+            .unwrap_or_else(|| key.to_string());
+        for (name, value) in args {
+            text = text.replace(&format!("{{ ${name} }}"), value);
+        }
+        text
+    }
+
+    // Rewrites numbers typed the local way into the form the parsers expect,
+    // so "36,6" reads as 36.6 in Polish and "1,000" as 1000 in English.
+    // Input already using a dot is left alone either way.
+    pub fn delocalize(&self, input: &str) -> String {
+        let chars: Vec<char> = input.chars().collect();
+        let mut out = String::with_capacity(input.len());
+        for (i, &c) in chars.iter().enumerate() {
+            let after_digit = i > 0 && chars[i - 1].is_ascii_digit();
+            let digits_after = chars[i + 1..]
+                .iter()
+                .take_while(|c| c.is_ascii_digit())
+                .count();
+            if c == self.decimal && c != '.' && after_digit && digits_after > 0 {
+                out.push('.');
+            } else if c == self.group && after_digit && digits_after == 3 {
+                continue;
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    pub fn parse_number(&self, input: &str) -> Option<f64> {
+        self.delocalize(input.trim()).parse().ok()
+    }
+
+    // Writes the decimal point of numbers in `output` the local way.
+    pub fn localize(&self, output: &str) -> String {
+        if self.decimal == '.' {
+            return output.to_string();
+        }
+        let chars: Vec<char> = output.chars().collect();
+        chars
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                let between_digits = i > 0
+                    && chars[i - 1].is_ascii_digit()
+                    && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit());
+                if c == '.' && between_digits {
+                    self.decimal
+                } else {
+                    c
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalogs_parse() {
+        let en = catalog("en").unwrap();
+        let pl = catalog("pl").unwrap();
+        // Every English message has a Polish translation.
+        let mut missing: Vec<&String> = en.keys().filter(|k| !pl.contains_key(*k)).collect();
+        missing.sort();
+        assert!(missing.is_empty(), "missing in pl.ftl: {missing:?}");
+        // A no-break space, so a plain space still separates arguments.
+        assert_eq!(pl["-group-separator"], "\u{a0}");
+        assert_eq!(en["-decimal-separator"], ".");
+        assert_eq!(
+            en["enter-celsius"],
+            "Enter temperature in Celsius:".to_string()
+        );
+        // Continuation lines lose four spaces of indentation and keep the rest.
+        assert!(
+            en["welcome"].starts_with("Welcome to Farenheit/Celsius converter!\nmenu 1  Convert")
+        );
+        assert!(pl["help"].starts_with("Polecenia:\n  menu 1, menu 2 "));
+        assert!(catalog("de").is_none());
+    }
+
+    #[test]
+    fn catalog_syntax() {
+        let messages = parse_catalog(
+            "# comment\nplain = a = b\nlong = first\n    second\n      indented\n\nspace = x{ \" \" }y{ \"  \" }z\nbroken = { \" open\n",
+        );
+        assert_eq!(messages["plain"], "a = b");
+        assert_eq!(messages["long"], "first\nsecond\n  indented");
+        assert_eq!(messages["space"], "x y  z");
+        assert_eq!(messages["broken"], "{ \" open");
+        assert_eq!(messages.len(), 4);
+    }
+
+    #[test]
+    fn missing_keys_and_arguments() {
+        let pl = Locale::new("pl_PL.UTF-8");
+        assert_eq!(
+            pl.format("error", &[("message", "zły argument")]),
+            "Błąd: zły argument"
+        );
+        assert_eq!(
+            pl.format("fahrenheit-is-celsius", &[("from", "98,6"), ("to", "37,0")]),
+            "98,6 °F to 37,0 °C"
+        );
+        // Unknown arguments are left in place, unknown keys show the key.
+        assert_eq!(pl.format("error", &[]), "Błąd: { $message }");
+        assert_eq!(pl.text("no-such-key"), "no-such-key");
+
+        // A key missing from the locale falls back to English.
+        let mut partial = Locale::new("pl");
+        partial.messages.remove("not-a-number");
+        assert_eq!(partial.text("not-a-number"), "Given entry is not a number!");
+
+        let fallback = Locale::new("de-DE");
+        assert_eq!(
+            fallback.text("invalid-option"),
+            "Provide option menu 1 or menu 2 only!"
+        );
+        assert_eq!(fallback.decimal, '.');
+        assert_eq!(Locale::new("").text("error"), "Error: { $message }");
+    }
+
+    #[test]
+    fn reads_local_numbers() {
+        let pl = Locale::new("pl");
+        assert_eq!(pl.delocalize("36,6"), "36.6");
+        assert_eq!(pl.delocalize("36.6"), "36.6");
+        assert_eq!(pl.delocalize("-36,6C to F"), "-36.6C to F");
+        assert_eq!(pl.delocalize("1\u{a0}000,5"), "1000.5");
+        assert_eq!(pl.delocalize("heat-index 32 100"), "heat-index 32 100");
+        assert_eq!(pl.delocalize("1,5e-3"), "1.5e-3");
+        // A comma between a number and a word still separates arguments.
+        assert_eq!(pl.delocalize("-10C, 30 km/h"), "-10C, 30 km/h");
+        assert_eq!(pl.parse_number(" 36,6 "), Some(36.6));
+        assert_eq!(pl.parse_number("36,6,6"), None);
+
+        let en = Locale::new("en_US");
+        assert_eq!(en.delocalize("1,000"), "1000");
+        assert_eq!(en.delocalize("36,6"), "36,6");
+        assert_eq!(en.parse_number("1,234.5"), Some(1234.5));
+        assert_eq!(en.parse_number("36,6"), None);
+    }
+
+    #[test]
+    fn writes_local_numbers() {
+        let pl = Locale::new("pl");
+        assert_eq!(pl.localize("37.00 C"), "37,00 C");
+        assert_eq!(pl.localize("-40.00 F"), "-40,00 F");
+        assert_eq!(pl.localize("-1.2345e-5 K"), "-1,2345e-5 K");
+        assert_eq!(pl.localize("6.0221e23"), "6,0221e23");
+        // Dots that are not decimal points stay.
+        assert_eq!(pl.localize("Done. See 3 ft."), "Done. See 3 ft.");
+        assert_eq!(pl.localize(".5 and 5."), ".5 and 5.");
+
+        let en = Locale::new("en");
+        assert_eq!(en.localize("-1.2345e-5 K"), "-1.2345e-5 K");
+    }
+}
@@ -1,66 +1,50 @@
-use std::io::{self, Write};
+use std::env;
+use std::process;
 
-fn rd_line(s: &mut String) {
-    io::stdin().read_line(s).expect("Cannot read STDIN");
-}
+use far_cel_core::units::Registry;
 
-fn calc_celsius(y: f64) -> f64 {
-    (y - 32.0) * (5.0 / 9.0)
-}
+mod chart;
+mod cli;
+mod currency;
+mod i18n;
+mod meteorology;
+mod repl;
+mod sensor_log;
+mod server;
+mod table;
+mod user_units;
 
-fn calc_farenheit(y: f64) -> f64 {
-    y * (9.0 / 5.0) + 32.0
+// Removes an option such as `--lang <LANG>` from the arguments, for the ones
+// that apply to every mode.
+fn take_option(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let at = args.iter().position(|a| a == name)?;
+    args.remove(at);
+    (at < args.len()).then(|| args.remove(at))
 }
 
 fn main() {
-    print!("Welcome to Farenheit/Celsius converter!\n1. Convert Farenheit to Celsius.\n2. Convert Celsius to Farenheit.\nPlease choose your option: ");
-    io::stdout().flush().expect("Failed to flush!");
-    loop {
-        let mut x = String::new();
-        rd_line(&mut x);
-        let x: i32 = match x.trim().parse() {
-            Ok(num) => num,
-            Err(_) => {
-                println!("Given entry is not a number!\nPlease choose your option: ");
-                io::stdout().flush().expect("Failed to flush!");
-                continue;
-            }
-        };
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let locale = i18n::Locale::select(take_option(&mut args, "--lang").as_deref());
+    let rates = take_option(&mut args, "--rates");
+    if args.first().is_some_and(|a| a == "rates") {
+        process::exit(currency::run(&args[1..], rates.as_deref()));
+    }
 
-        if x == 1 {
-            let mut y = String::new();
-            print!("Enter temperature in Farenheit: ");
-            io::stdout().flush().expect("Failed to flush!");
-            rd_line(&mut y);
-            let y: f64 = match y.trim().parse() {
-                Ok(num) => num,
-                Err(_) => {
-                    println!("Given entry is not a number!");
-                    break;
-                }
-            };
-            let answer: f64 = calc_celsius(y);
-            print!("{y:.2} Farenheit is equal to {answer:.2} Celsius");
-            break;
-        } else if x == 2 {
-            let mut y = String::new();
-            print!("Enter temperature in Celsius: ");
-            io::stdout().flush().expect("Failed to flush!");
-            rd_line(&mut y);
-            let y: f64 = match y.trim().parse() {
-                Ok(num) => num,
-                Err(_) => {
-                    println!("Given entry is not a number!");
-                    break;
-                }
-            };
-            let answer: f64 = calc_farenheit(y);
-            print!("{y:.2} Celsius is equal to {answer:.2} Farenheit");
-            break;
-        } else {
-            print!("Provide option 1 or 2 only!\nPlease choose your option: ");
-            io::stdout().flush().expect("Failed to flush!");
-            continue;
-        }
+    let mut registry = Registry::builtin();
+    let units = take_option(&mut args, "--units");
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        // Help needs neither rates nor units, so a broken file cannot hide it.
+        process::exit(cli::run(&args, &locale, &registry));
     }
+    let loaded = currency::load(&mut registry, rates.as_deref())
+        .and_then(|_| user_units::load(&mut registry, units.as_deref()));
+    if let Err(e) = loaded {
+        eprintln!("{}", locale.format("error", &[("message", &e)]));
+        process::exit(2);
+    }
+    if !args.is_empty() {
+        process::exit(cli::run(&args, &locale, &registry));
+    }
+
+    repl::run(&locale, registry);
 }
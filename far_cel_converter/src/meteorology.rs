@@ -0,0 +1,157 @@
+use far_cel_core::expr::{self, Quantity, Variables};
+use far_cel_core::temperature::Kelvin;
+use far_cel_core::units::{Dimension, Registry, Unit};
+use far_cel_core::weather::{self, Estimate};
+
+use crate::i18n::Locale;
+
+pub const COMMANDS: [&str; 4] = ["heat-index", "wind-chill", "dew-point", "humidity"];
+
+const USAGE: &str = "Usage:
+  far_cel_converter heat-index <TEMP> <HUMIDITY>   e.g. heat-index 32C 60%
+  far_cel_converter wind-chill <TEMP> <WIND>       e.g. wind-chill -10C, 30 km/h
+  far_cel_converter dew-point <TEMP> <HUMIDITY>    e.g. dew-point 77F 60%
+  far_cel_converter humidity <TEMP> <DEW POINT>    e.g. humidity 25C 16.7C
+
+Temperatures may be in any scale, results are shown in the scale of the first
+one unless --to names another. Separate the two inputs with a comma when they
+contain spaces. A warning is printed when the inputs are outside the range the
+formula is valid for:
+  heat index   80 °F to 110 °F (26.7 °C to 43.3 °C), 40% to 100% humidity
+  wind chill   at or below 50 °F (10 °C), winds from 3 mph (4.8 km/h)
+  dew point    -30 °C to 35 °C (-22 °F to 95 °F)
+  humidity     -30 °C to 35 °C (-22 °F to 95 °F)
+
+Options:
+  --to <UNIT>   temperature scale of the result";
+
+// Splits "32 C, 60%" or "32C 60%" into its two inputs.
+fn inputs(text: &str) -> Result<(&str, &str), String> {
+    if let Some((first, second)) = text.split_once(',') {
+        return Ok((first.trim(), second.trim()));
+    }
+    match text.split_whitespace().collect::<Vec<_>>()[..] {
+        [first, second] => Ok((first, second)),
+        _ => Err(format!(
+            "expected two inputs separated by a comma, got '{text}'"
+        )),
+    }
+}
+
+// A temperature reading and the unit it was written in.
+fn temperature(text: &str, registry: &Registry) -> Result<(Kelvin, Unit), String> {
+    let (quantity, _) = expr::evaluate(text, registry, &Variables::new())?;
+    match quantity.unit {
+        Some(unit) if quantity.dimension == Dimension::TEMPERATURE && quantity.absolute => {
+            Ok((Kelvin::new(quantity.value)?, unit))
+        }
+        _ => Err(format!(
+            "'{text}' is not a temperature reading such as 25C or 77F"
+        )),
+    }
+}
+
+fn humidity(text: &str) -> Result<f64, String> {
+    text.trim_end_matches('%')
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{text}' is not a relative humidity such as 60%"))
+}
+
+fn wind_mph(text: &str, registry: &Registry) -> Result<f64, String> {
+    let (quantity, _) = expr::evaluate(text, registry, &Variables::new())?;
+    if quantity.dimension != Dimension::LENGTH / Dimension::TIME {
+        return Err(format!(
+            "'{text}' is not a wind speed such as 15 mph or 20 km/h"
+        ));
+    }
+    let mph = registry.find("mph")?;
+    Ok(quantity.in_unit(mph))
+}
+
+fn in_kelvin<T: Into<Kelvin>>(estimate: Estimate<T>) -> Estimate<Kelvin> {
+    Estimate {
+        value: estimate.value.into(),
+        warnings: estimate.warnings,
+    }
+}
+
+// Runs one of COMMANDS on its inputs, e.g. "32C, 60%" for heat-index,
+// and formats the result in `to` or the scale of the first temperature.
+pub fn calculate(
+    command: &str,
+    text: &str,
+    to: Option<&Unit>,
+    registry: &Registry,
+) -> Result<Estimate<String>, String> {
+    let (first, second) = inputs(text)?;
+    let (t, unit) = temperature(first, registry)?;
+    let unit = to.unwrap_or(&unit);
+    let show = |estimate: Estimate<Kelvin>| -> Result<Estimate<String>, String> {
+        let kelvin = registry.find("K")?;
+        let value = Quantity::of(estimate.value.value(), kelvin).convert_to(unit)?;
+        Ok(Estimate {
+            value: value.to_string(),
+            warnings: estimate.warnings,
+        })
+    };
+    match command {
+        "heat-index" => show(in_kelvin(weather::heat_index(t, humidity(second)?)?)),
+        "wind-chill" => show(in_kelvin(weather::wind_chill(
+            t,
+            wind_mph(second, registry)?,
+        )?)),
+        "dew-point" => show(in_kelvin(weather::dew_point(t, humidity(second)?)?)),
+        "humidity" => {
+            let (dew_point, _) = temperature(second, registry)?;
+            let estimate = weather::relative_humidity(t, dew_point)?;
+            Ok(Estimate {
+                value: format!("{:.2}%", estimate.value),
+                warnings: estimate.warnings,
+            })
+        }
+        _ => Err(format!("unknown command '{command}'")),
+    }
+}
+
+// Runs `far_cel_converter heat-index ...` and friends, returning the exit code.
+pub fn run(command: &str, args: &[String], registry: &Registry, locale: &Locale) -> i32 {
+    let mut words = Vec::new();
+    let mut to = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return 0;
+            }
+            "--to" => match args.next().map(|name| registry.find(name)) {
+                Some(Ok(unit)) => to = Some(unit),
+                Some(Err(e)) => {
+                    eprintln!("Error: {e}");
+                    return 2;
+                }
+                None => {
+                    eprintln!("Error: --to needs a unit\n{USAGE}");
+                    return 2;
+                }
+            },
+            _ => words.push(arg.as_str()),
+        }
+    }
+
+    let text = locale.delocalize(&words.join(" "));
+    match calculate(command, &text, to, registry) {
+        Ok(estimate) => {
+            println!("{}", locale.localize(&estimate.value));
+            for warning in &estimate.warnings {
+                eprintln!("{}", locale.format("warning", &[("message", warning)]));
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", locale.format("error", &[("message", &e)]));
+            1
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::env;
+use std::path::PathBuf;
+
+use far_cel_core::expr::{self, Quantity, Variables};
+use far_cel_core::temperature::{calc_celsius, calc_farenheit, Celsius, Fahrenheit};
+use far_cel_core::units::{Dimension, Registry, Unit};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+use crate::currency;
+use crate::i18n::Locale;
+use crate::meteorology;
+
+enum Flow {
+    Continue,
+    Quit,
+}
+
+struct Session<'a> {
+    locale: &'a Locale,
+    editor: DefaultEditor,
+    registry: Registry,
+    vars: Variables,
+}
+
+fn history_path() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".far_cel_converter_history"))
+}
+
+impl Session<'_> {
+    fn handle(&mut self, line: &str) -> Flow {
+        match line {
+            "quit" | "exit" => return Flow::Quit,
+            "help" | "?" => println!("{}", self.locale.text("help")),
+            "units" | "list-units" => self.list_units(),
+            "vars" => self.list_vars(),
+            _ => {
+                let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+                if command == "menu" {
+                    self.menu(rest.trim().parse().unwrap_or(0));
+                } else if meteorology::COMMANDS.contains(&command) {
+                    self.weather(command, rest);
+                } else if let Some((name, expression)) = line.split_once('=') {
+                    self.assign(name.trim(), expression);
+                } else {
+                    match self.calculate(line) {
+                        Ok(result) => {
+                            let text = currency::annotate(
+                                result.to_string(),
+                                result.dimension,
+                                &self.registry,
+                            );
+                            println!("{}", self.locale.localize(&text));
+                        }
+                        Err(e) => self.error(&e),
+                    }
+                }
+            }
+        }
+        Flow::Continue
+    }
+
+    fn error(&self, message: &str) {
+        println!("{}", self.locale.format("error", &[("message", message)]));
+    }
+
+    // The two conversions of the original menu, now "menu 1" and "menu 2" so
+    // that a bare number is an expression like any other.
+    fn menu(&mut self, option: i32) {
+        type Calc = fn(f64) -> Result<f64, String>;
+        let (prompt, result, to, calc): (&str, &str, &str, Calc) = match option {
+            1 => ("enter-fahrenheit", "fahrenheit-is-celsius", "C", |y| {
+                Ok(calc_celsius(Fahrenheit::new(y)?).value())
+            }),
+            2 => ("enter-celsius", "celsius-is-fahrenheit", "F", |y| {
+                Ok(calc_farenheit(Celsius::new(y)?).value())
+            }),
+            _ => {
+                println!("{}", self.locale.text("invalid-option"));
+                return;
+            }
+        };
+
+        let prompt = format!("{} ", self.locale.text(prompt));
+        let y = match self.editor.readline(&prompt) {
+            Ok(y) => y,
+            Err(_) => return,
+        };
+        let y: f64 = match self.locale.parse_number(&y) {
+            Some(num) => num,
+            None => {
+                println!("{}", self.locale.text("not-a-number"));
+                return;
+            }
+        };
+        let answer = match calc(y) {
+            Ok(answer) => answer,
+            Err(e) => {
+                self.error(&e);
+                return;
+            }
+        };
+        let (from, to_text) = (format!("{y:.2}"), format!("{answer:.2}"));
+        let line = self
+            .locale
+            .format(result, &[("from", &from), ("to", &to_text)]);
+        println!("{}", self.locale.localize(&line));
+
+        if let Some(unit) = self.registry.lookup(to) {
+            self.vars
+                .insert("ans".to_string(), Quantity::of(answer, unit));
+        }
+    }
+
+    fn weather(&self, command: &str, inputs: &str) {
+        let inputs = self.locale.delocalize(inputs);
+        match meteorology::calculate(command, &inputs, None, &self.registry) {
+            Ok(estimate) => {
+                println!("{}", self.locale.localize(&estimate.value));
+                for warning in &estimate.warnings {
+                    let line = self.locale.format("warning", &[("message", warning)]);
+                    println!("{}", self.locale.localize(&line));
+                }
+            }
+            Err(e) => self.error(&e),
+        }
+    }
+
+    fn calculate(&mut self, line: &str) -> Result<Quantity, String> {
+        let line = self.locale.delocalize(line);
+        let (quantity, target) = expr::evaluate(&line, &self.registry, &self.vars)?;
+        let target = target.or_else(|| self.registry.opposite(quantity.unit.as_ref()?).cloned());
+        let quantity = match target {
+            Some(unit) => quantity.convert_to(&unit)?,
+            None => quantity,
+        };
+        self.vars.insert("ans".to_string(), quantity.clone());
+        Ok(quantity)
+    }
+
+    fn assign(&mut self, name: &str, expression: &str) {
+        let name = name.strip_prefix("let ").unwrap_or(name).trim();
+        let valid = name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if !valid || name == "ans" || self.registry.lookup(name).is_some() {
+            println!(
+                "{}",
+                self.locale.format("invalid-variable", &[("name", name)])
+            );
+            return;
+        }
+
+        match self.calculate(expression) {
+            Ok(result) => {
+                let line = format!("{name} = {result}");
+                println!("{}", self.locale.localize(&line));
+                self.vars.insert(name.to_string(), result);
+            }
+            Err(e) => self.error(&e),
+        }
+    }
+
+    fn list_vars(&self) {
+        let mut names: Vec<&String> = self.vars.keys().collect();
+        names.sort();
+        for name in names {
+            let line = format!("{name} = {}", self.vars[name]);
+            println!("{}", self.locale.localize(&line));
+        }
+    }
+
+    fn list_units(&self) {
+        let mut groups: Vec<(Dimension, Vec<&Unit>)> = Vec::new();
+        for unit in self.registry.units() {
+            match groups.iter_mut().find(|(d, _)| *d == unit.dimension) {
+                Some((_, units)) => units.push(unit),
+                None => groups.push((unit.dimension, vec![unit])),
+            }
+        }
+        for (dimension, units) in groups {
+            let names: Vec<String> = units
+                .iter()
+                .map(|u| format!("{} ({})", u.symbol, u.name))
+                .collect();
+            println!("{}: {}", dimension.describe(), names.join(", "));
+        }
+    }
+}
+
+pub fn run(locale: &Locale, registry: Registry) {
+    println!("{}", locale.text("welcome"));
+
+    let mut session = Session {
+        locale,
+        editor: DefaultEditor::new().expect("Cannot open the terminal"),
+        registry,
+        vars: Variables::new(),
+    };
+    let history = history_path();
+    if let Some(path) = &history {
+        // There is no history file before the first session.
+        let _ = session.editor.load_history(path);
+    }
+
+    loop {
+        match session.editor.readline("> ") {
+            Ok(line) => {
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                let _ = session.editor.add_history_entry(line);
+                if let Flow::Quit = session.handle(line) {
+                    break;
+                }
+            }
+            Err(ReadlineError::Interrupted) => println!("{}", locale.text("interrupted")),
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                eprintln!("Cannot read STDIN: {e}");
+                break;
+            }
+        }
+    }
+
+    if let Some(path) = &history {
+        if let Err(e) = session.editor.save_history(path) {
+            eprintln!("Failed to save history: {e}");
+        }
+    }
+}
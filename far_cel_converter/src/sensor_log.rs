@@ -0,0 +1,353 @@
+use std::fs::File;
+use std::io::{self, Read, Write};
+
+use far_cel_core::expr::Quantity;
+use far_cel_core::units::{Registry, Unit};
+
+const USAGE: &str = "Usage:
+  far_cel_converter csv [<FILE>] --column <NAME> --from <UNIT> [--to <UNIT>] [--output <FILE>]
+      e.g. csv device.log.csv --column temp_f --column probe_f --from F --to C -o device_c.csv
+
+Reads the CSV from <FILE> or stdin and writes it to --output or stdout one row
+at a time, converting the named columns. Cells that cannot be converted are
+reported on stderr with their line number and copied unchanged.
+
+Options:
+  --column <NAME>    column to convert, repeat it or separate names with commas
+  --from <UNIT>      unit the columns are in
+  --to <UNIT>        unit to convert them to, the other scale for F and C
+  --output <FILE>    where to write the result, -o for short
+  --decimals <N>     decimals of the converted values, 2 by default
+  --delimiter <C>    field separator of input and output, ',' by default";
+
+pub struct Spec {
+    pub columns: Vec<String>,
+    pub from: Unit,
+    pub to: Unit,
+    pub decimals: usize,
+    pub delimiter: u8,
+}
+
+// Rows written and cells that could not be converted.
+#[derive(Debug, Default, PartialEq)]
+pub struct Stats {
+    pub rows: u64,
+    pub failed: u64,
+}
+
+fn convert_cell(text: &str, spec: &Spec) -> Result<String, String> {
+    let value: f64 = text
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{text}' is not a number"))?;
+    let converted = Quantity::of(value, &spec.from)
+        .checked()?
+        .convert_to(&spec.to)?
+        .display_value();
+    Ok(format!("{converted:.*}", spec.decimals))
+}
+
+// Copies `input` to `output`, converting the columns of `spec` and passing
+// a message for each bad cell or row to `report`. Only an I/O error or a
+// missing column stops it early.
+pub fn convert(
+    input: impl Read,
+    output: impl Write,
+    spec: &Spec,
+    mut report: impl FnMut(String),
+) -> Result<Stats, String> {
+    // Both sides buffer internally, so plain files and stdio are fine here.
+    // Rows must be as long as the header, a ragged one is skipped below.
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(spec.delimiter)
+        .from_reader(input);
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(spec.delimiter)
+        .from_writer(output);
+
+    let headers = reader.byte_headers().map_err(|e| e.to_string())?.clone();
+    let mut targets = Vec::new();
+    for name in &spec.columns {
+        match headers.iter().position(|h| h == name.as_bytes()) {
+            Some(at) => targets.push((at, name)),
+            None => return Err(format!("no column named '{name}'")),
+        }
+    }
+    writer
+        .write_byte_record(&headers)
+        .map_err(|e| e.to_string())?;
+
+    let mut stats = Stats::default();
+    let mut record = csv::ByteRecord::new();
+    loop {
+        match reader.read_byte_record(&mut record) {
+            Ok(true) => {}
+            Ok(false) => break,
+            // A bad row is skipped, but a read that failed, like a broken
+            // pipe or a file that went away, would only fail again.
+            Err(e) => match e.kind() {
+                csv::ErrorKind::UnequalLengths {
+                    pos,
+                    expected_len,
+                    len,
+                } => {
+                    let line = pos.as_ref().map_or(0, |p| p.line());
+                    report(format!(
+                        "line {line}: row skipped, it has {len} fields instead of {expected_len}"
+                    ));
+                    stats.failed += 1;
+                    continue;
+                }
+                _ => return Err(format!("reading stopped after row {}: {e}", stats.rows)),
+            },
+        }
+        let line = record.position().map_or(0, |p| p.line());
+        let mut cells: Vec<Vec<u8>> = record.iter().map(<[u8]>::to_vec).collect();
+        for (at, name) in &targets {
+            // An empty cell is a missing reading, not an error.
+            let Some(cell) = cells.get_mut(*at).filter(|c| !c.trim_ascii().is_empty()) else {
+                continue;
+            };
+            let converted = std::str::from_utf8(cell)
+                .map_err(|_| "the cell is not valid UTF-8".to_string())
+                .and_then(|text| convert_cell(text, spec));
+            match converted {
+                Ok(text) => *cell = text.into_bytes(),
+                Err(e) => {
+                    report(format!("line {line}, column {name}: {e}"));
+                    stats.failed += 1;
+                }
+            }
+        }
+        writer.write_record(&cells).map_err(|e| e.to_string())?;
+        stats.rows += 1;
+    }
+    writer.flush().map_err(|e| e.to_string())?;
+    Ok(stats)
+}
+
+fn parse_args<'a>(
+    args: &'a [String],
+    registry: &Registry,
+) -> Result<(Spec, Option<&'a str>, Option<&'a str>), String> {
+    let mut columns = Vec::new();
+    let (mut from, mut to) = (None, None);
+    let (mut input, mut output) = (None, None);
+    let mut decimals = 2;
+    let mut delimiter = b',';
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') || arg == "-" {
+            if input.replace(arg.as_str()).is_some() {
+                return Err(format!("unexpected argument '{arg}'"));
+            }
+            continue;
+        }
+        let value = args.next().ok_or_else(|| format!("{arg} needs a value"))?;
+        match arg.as_str() {
+            "--column" => columns.extend(value.split(',').map(|c| c.trim().to_string())),
+            "--from" => from = Some(registry.find(value)?.clone()),
+            "--to" => to = Some(registry.find(value)?.clone()),
+            "--output" | "-o" => output = Some(value.as_str()),
+            "--decimals" => {
+                decimals = value
+                    .parse()
+                    .map_err(|_| format!("invalid number of decimals '{value}'"))?
+            }
+            "--delimiter" => {
+                delimiter = match value.as_bytes() {
+                    [c] => *c,
+                    b"\\t" | b"tab" => b'\t',
+                    _ => {
+                        return Err(format!(
+                            "the delimiter must be one character, got '{value}'"
+                        ))
+                    }
+                }
+            }
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+
+    if columns.is_empty() {
+        return Err("name the columns to convert with --column".to_string());
+    }
+    let from = from.ok_or("name the unit of the columns with --from")?;
+    let to = match to {
+        Some(unit) => unit,
+        None => registry
+            .opposite(&from)
+            .cloned()
+            .ok_or("name the unit to convert to with --to")?,
+    };
+    if from.dimension != to.dimension {
+        return Err(format!(
+            "cannot convert {} to {}, which is {}",
+            from.dimension.describe(),
+            to.symbol,
+            to.dimension.describe()
+        ));
+    }
+    let spec = Spec {
+        columns,
+        from,
+        to,
+        decimals,
+        delimiter,
+    };
+    Ok((spec, input.filter(|i| *i != "-"), output))
+}
+
+// Runs `far_cel_converter csv ...` and returns the process exit code.
+pub fn run(args: &[String], registry: &Registry) -> i32 {
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{USAGE}");
+        return 0;
+    }
+    let (spec, input, output) = match parse_args(args, registry) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Error: {e}\n{USAGE}");
+            return 2;
+        }
+    };
+
+    let input: Box<dyn Read> = match input {
+        Some(path) => match File::open(path) {
+            Ok(file) => Box::new(file),
+            Err(e) => {
+                eprintln!("Error: cannot read {path}: {e}");
+                return 1;
+            }
+        },
+        None => Box::new(io::stdin().lock()),
+    };
+    let output: Box<dyn Write> = match output {
+        Some(path) => match File::create(path) {
+            Ok(file) => Box::new(file),
+            Err(e) => {
+                eprintln!("Error: cannot write {path}: {e}");
+                return 1;
+            }
+        },
+        None => Box::new(io::stdout().lock()),
+    };
+
+    match convert(input, output, &spec, |message| eprintln!("{message}")) {
+        Ok(Stats { failed: 0, .. }) => 0,
+        Ok(stats) => {
+            eprintln!(
+                "{} of the cells could not be converted, {} rows written",
+                stats.failed, stats.rows
+            );
+            1
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = include_str!("../tests/fixtures/sensor_log.csv");
+
+    fn spec(columns: &[&str], from: &str, to: &str) -> Spec {
+        let registry = Registry::builtin();
+        Spec {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            from: registry.lookup(from).unwrap().clone(),
+            to: registry.lookup(to).unwrap().clone(),
+            decimals: 2,
+            delimiter: b',',
+        }
+    }
+
+    fn run(input: &str, spec: &Spec) -> (Result<Stats, String>, String, Vec<String>) {
+        let mut output = Vec::new();
+        let mut reports = Vec::new();
+        let stats = convert(input.as_bytes(), &mut output, spec, |m| reports.push(m));
+        (stats, String::from_utf8(output).unwrap(), reports)
+    }
+
+    #[test]
+    fn converts_named_columns() {
+        let (stats, output, reports) = run(LOG, &spec(&["temp_f", "probe_f"], "F", "C"));
+        assert_eq!(stats, Ok(Stats { rows: 6, failed: 3 }));
+        assert_eq!(
+            output.lines().take(3).collect::<Vec<_>>(),
+            [
+                "time,device,temp_f,probe_f,humidity",
+                "2024-07-01T00:00:00Z,\"sensor 1, roof\",-40.00,0.00,41",
+                "2024-07-01T00:05:00Z,\"sensor 1, roof\",37.00,0.00,40",
+            ]
+        );
+        assert_eq!(
+            reports,
+            [
+                "line 4, column temp_f: 'ERR' is not a number",
+                "line 6, column probe_f: -500.00 F is below absolute zero",
+                "line 7: row skipped, it has 4 fields instead of 5",
+            ]
+        );
+        // Bad and missing cells are copied as they were, short rows are left out.
+        assert!(output.contains(",ERR,"));
+        assert!(output.contains("2024-07-01T00:20:00Z,sensor 2,,,"));
+        assert!(!output.contains("n/a"));
+    }
+
+    #[test]
+    fn ragged_rows_are_skipped() {
+        let input = "time,temp_f,note\n1,32\n2,212,ok\n3,50,too,long\n";
+        let (stats, output, reports) = run(input, &spec(&["temp_f"], "F", "C"));
+        assert_eq!(stats, Ok(Stats { rows: 1, failed: 2 }));
+        assert_eq!(output, "time,temp_f,note\n2,100.00,ok\n");
+        assert_eq!(
+            reports,
+            [
+                "line 2: row skipped, it has 2 fields instead of 3",
+                "line 4: row skipped, it has 4 fields instead of 3",
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_column() {
+        let (stats, _, _) = run(LOG, &spec(&["temp_c"], "C", "F"));
+        assert_eq!(stats, Err("no column named 'temp_c'".to_string()));
+    }
+
+    // Gives `data`, then fails on every read after it.
+    struct Failing<'a> {
+        data: &'a [u8],
+    }
+
+    impl Read for Failing<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.data.is_empty() {
+                return Err(io::Error::other("device went away"));
+            }
+            self.data.read(buf)
+        }
+    }
+
+    #[test]
+    fn read_errors_stop_the_conversion() {
+        let input = Failing {
+            data: b"time,temp_f\n2024-07-01T00:00:00Z,32\n",
+        };
+        let mut reports = Vec::new();
+        let stats = convert(input, io::sink(), &spec(&["temp_f"], "F", "C"), |m| {
+            reports.push(m)
+        });
+        assert_eq!(
+            stats,
+            Err("reading stopped after row 1: device went away".to_string())
+        );
+        assert!(reports.is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+use std::io::{self, BufRead, Write};
+
+use far_cel_core::expr::{self, Quantity, Variables};
+use far_cel_core::units::{Dimension, Registry};
+use serde_json::{json, Map, Value};
+
+use crate::currency;
+
+// Error codes of the JSON-RPC 2.0 specification, plus one of our own for
+// requests that are well formed but cannot be converted.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const CONVERSION_FAILED: i64 = -32000;
+
+struct Error {
+    code: i64,
+    message: String,
+}
+
+impl Error {
+    fn new(code: i64, message: impl Into<String>) -> Error {
+        Error {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+// Anything our conversions report is a conversion failure.
+impl From<String> for Error {
+    fn from(message: String) -> Error {
+        Error::new(CONVERSION_FAILED, message)
+    }
+}
+
+fn string<'a>(params: &'a Map<String, Value>, name: &str) -> Result<Option<&'a str>, Error> {
+    match params.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(text)) => Ok(Some(text)),
+        Some(_) => Err(Error::new(
+            INVALID_PARAMS,
+            format!("'{name}' must be a string"),
+        )),
+    }
+}
+
+fn required<'a>(params: &'a Map<String, Value>, name: &str) -> Result<&'a str, Error> {
+    string(params, name)?.ok_or_else(|| Error::new(INVALID_PARAMS, format!("missing '{name}'")))
+}
+
+fn describe(quantity: &Quantity, registry: &Registry) -> Value {
+    json!({
+        "value": quantity.display_value(),
+        "unit": quantity.label(),
+        "dimension": quantity.dimension.describe(),
+        "text": currency::annotate(quantity.to_string(), quantity.dimension, registry),
+    })
+}
+
+// {"value": 98.6, "from": "F", "to": "C"}, `to` defaults to the other scale.
+fn convert(params: &Map<String, Value>, registry: &Registry) -> Result<Value, Error> {
+    let value = params
+        .get("value")
+        .and_then(Value::as_f64)
+        .ok_or_else(|| Error::new(INVALID_PARAMS, "'value' must be a number"))?;
+    let from = registry.find(required(params, "from")?)?;
+    let to = match string(params, "to")? {
+        Some(name) => registry.find(name)?,
+        None => registry
+            .opposite(from)
+            .ok_or_else(|| Error::new(INVALID_PARAMS, "missing 'to'"))?,
+    };
+    let quantity = Quantity::of(value, from).checked()?.convert_to(to)?;
+    Ok(describe(&quantity, registry))
+}
+
+// {"expression": "3 ft + 20 in to cm"}, converted to its target if it names one.
+fn parse_expression(params: &Map<String, Value>, registry: &Registry) -> Result<Value, Error> {
+    let expression = required(params, "expression")?;
+    let (quantity, target) = expr::evaluate(expression, registry, &Variables::new())?;
+    let quantity = match &target {
+        Some(unit) => quantity.convert_to(unit)?,
+        None => quantity.checked()?,
+    };
+    let mut result = describe(&quantity, registry);
+    result["target"] = json!(target.map(|unit| unit.symbol));
+    Ok(result)
+}
+
+// {} for every unit, or {"dimension": "length"} for the units of one.
+fn list_units(params: &Map<String, Value>, registry: &Registry) -> Result<Value, Error> {
+    let dimension = match string(params, "dimension")? {
+        Some(name) => Some(
+            Dimension::from_name(name)
+                .ok_or_else(|| Error::new(INVALID_PARAMS, format!("unknown dimension '{name}'")))?,
+        ),
+        None => None,
+    };
+    let units: Vec<Value> = registry
+        .units()
+        .iter()
+        .filter(|unit| dimension.is_none_or(|d| unit.dimension == d))
+        .map(|unit| {
+            json!({
+                "symbol": unit.symbol,
+                "name": unit.name,
+                "aliases": unit.aliases,
+                "dimension": unit.dimension.describe(),
+            })
+        })
+        .collect();
+    Ok(Value::Array(units))
+}
+
+fn call(request: &Value, registry: &Registry) -> Result<Value, Error> {
+    let request = request
+        .as_object()
+        .filter(|r| r.get("jsonrpc").and_then(Value::as_str) == Some("2.0"))
+        .ok_or_else(|| Error::new(INVALID_REQUEST, "not a JSON-RPC 2.0 request"))?;
+    let method = request
+        .get("method")
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::new(INVALID_REQUEST, "missing 'method'"))?;
+    let empty = Map::new();
+    let params = match request.get("params") {
+        None => &empty,
+        Some(Value::Object(params)) => params,
+        Some(_) => {
+            return Err(Error::new(
+                INVALID_PARAMS,
+                "params must be an object with named members",
+            ))
+        }
+    };
+
+    match method {
+        "convert" => convert(params, registry),
+        "parse-expression" => parse_expression(params, registry),
+        "list-units" => list_units(params, registry),
+        _ => Err(Error::new(
+            METHOD_NOT_FOUND,
+            format!("unknown method '{method}'"),
+        )),
+    }
+}
+
+// The response to one request, or None for a notification (no "id").
+fn respond(request: &Value, registry: &Registry) -> Option<Value> {
+    let id = request.get("id");
+    let result = call(request, registry);
+    if id.is_none() && request.get("method").is_some() {
+        return None;
+    }
+    let id = id.cloned().unwrap_or(Value::Null);
+    Some(match result {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        Err(e) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {"code": e.code, "message": e.message},
+        }),
+    })
+}
+
+// Answers one line: a request, or an array of them sent as a batch.
+fn handle(line: &str, registry: &Registry) -> Option<Value> {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => {
+            return Some(json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": {"code": PARSE_ERROR, "message": e.to_string()},
+            }))
+        }
+    };
+    match request {
+        Value::Array(batch) if batch.is_empty() => respond(&Value::Null, registry),
+        Value::Array(batch) => {
+            let responses: Vec<Value> = batch
+                .iter()
+                .filter_map(|request| respond(request, registry))
+                .collect();
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        request => respond(&request, registry),
+    }
+}
+
+// Runs `far_cel_converter --serve-stdio`: one JSON-RPC request per line on
+// stdin, one response per line on stdout, until stdin is closed.
+pub fn run(registry: &Registry) -> i32 {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for line in io::stdin().lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                eprintln!("Error: cannot read stdin: {e}");
+                return 1;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle(&line, registry) {
+            // Flush every response, the client waits for it before sending more.
+            if writeln!(out, "{response}")
+                .and_then(|_| out.flush())
+                .is_err()
+            {
+                return 1;
+            }
+        }
+    }
+    0
+}
@@ -0,0 +1,333 @@
+use std::fs;
+
+use far_cel_core::expr::{self, Quantity, Variables};
+use far_cel_core::units::{format_number, Registry, Unit};
+use serde_json::json;
+
+use crate::chart;
+use crate::i18n::Locale;
+
+// Guards against a step so small the table would never end.
+const MAX_ROWS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Csv,
+    Markdown,
+    Json,
+}
+
+pub struct Table {
+    pub from: Unit,
+    pub to: Unit,
+    pub rows: Vec<(f64, f64)>,
+}
+
+// Drops the last bits of f64 noise, so -40F stays -40 after a round trip
+// through kelvin and a row lands on 0 rather than -7e-15.
+fn tidy(value: f64, step: f64) -> f64 {
+    if value.abs() < step * 1e-9 {
+        return 0.0;
+    }
+    format!("{value:.12e}").parse().unwrap_or(value)
+}
+
+// Reads one end of the range, e.g. "-40F", as a value in its own unit.
+fn endpoint(text: &str, registry: &Registry) -> Result<(f64, Unit), String> {
+    let (quantity, _) = expr::evaluate(text, registry, &Variables::new())?;
+    let unit = quantity
+        .unit
+        .clone()
+        .ok_or_else(|| format!("'{text}' needs a unit"))?;
+    Ok((quantity.display_value(), unit))
+}
+
+impl Table {
+    // Builds the rows for a range such as "-40F to 212F step 10".
+    pub fn build(spec: &str, to: Option<Unit>, registry: &Registry) -> Result<Table, String> {
+        let words: Vec<&str> = spec.split_whitespace().collect();
+        let to_at = words.iter().position(|w| *w == "to");
+        let step_at = words.iter().position(|w| *w == "step");
+        let (to_at, step_at) = match (to_at, step_at) {
+            (Some(t), Some(s)) if 0 < t && t + 1 < s && s + 1 < words.len() => (t, s),
+            _ => {
+                return Err(format!(
+                    "expected '<FROM> to <END> step <STEP>', got '{spec}'"
+                ))
+            }
+        };
+
+        let (start, from) = endpoint(&words[..to_at].join(" "), registry)?;
+        let start = tidy(start, 0.0);
+        let (end, end_unit) = endpoint(&words[to_at + 1..step_at].join(" "), registry)?;
+        let end = Quantity::of(end, &end_unit)
+            .convert_to(&from)?
+            .display_value();
+        let step_text = words[step_at + 1..].join(" ");
+        let step: f64 = step_text
+            .parse()
+            .map_err(|_| format!("step '{step_text}' is not a number"))?;
+        if step <= 0.0 || !step.is_finite() {
+            return Err("step must be a positive number".to_string());
+        }
+
+        let to = match to {
+            Some(unit) => unit,
+            None => registry
+                .opposite(&from)
+                .cloned()
+                .ok_or("name the unit of the second column with --to")?,
+        };
+
+        let count = ((end - start) / step + 1e-9).floor();
+        if count < 0.0 {
+            return Err("the range ends before it starts".to_string());
+        }
+        if count as usize >= MAX_ROWS {
+            return Err(format!("the table would have more than {MAX_ROWS} rows"));
+        }
+
+        let mut rows = Vec::new();
+        for i in 0..=count as usize {
+            // Multiplying instead of adding up steps keeps rounding error out of the rows.
+            let value = tidy(start + i as f64 * step, step);
+            let converted = Quantity::of(value, &from).convert_to(&to)?.display_value();
+            rows.push((value, tidy(converted, step)));
+        }
+        Ok(Table { from, to, rows })
+    }
+
+    // Text and Markdown are for people and use the locale's decimal
+    // separator, CSV and JSON are for other programs and keep the dot.
+    fn render(&self, format: Format, locale: &Locale) -> String {
+        let number = |value: f64| match format {
+            Format::Text | Format::Markdown => locale.localize(&format_number(value)),
+            Format::Csv | Format::Json => format_number(value),
+        };
+        let cells: Vec<(String, String)> = self
+            .rows
+            .iter()
+            .map(|(a, b)| (number(*a), number(*b)))
+            .collect();
+        let (left, right) = (&self.from.symbol, &self.to.symbol);
+
+        match format {
+            Format::Text => {
+                let width_a = cells.iter().map(|c| c.0.len()).chain([left.len()]).max();
+                let width_b = cells.iter().map(|c| c.1.len()).chain([right.len()]).max();
+                let (width_a, width_b) = (width_a.unwrap_or(0), width_b.unwrap_or(0));
+                let mut out = format!("{left:>width_a$}  {right:>width_b$}\n");
+                for (a, b) in &cells {
+                    out += &format!("{a:>width_a$}  {b:>width_b$}\n");
+                }
+                out
+            }
+            Format::Csv => {
+                let mut out = format!("{left},{right}\n");
+                for (a, b) in &cells {
+                    out += &format!("{a},{b}\n");
+                }
+                out
+            }
+            Format::Markdown => {
+                let mut out = format!("| {left} | {right} |\n|---:|---:|\n");
+                for (a, b) in &cells {
+                    out += &format!("| {a} | {b} |\n");
+                }
+                out
+            }
+            Format::Json => {
+                let rows: Vec<_> = self.rows.iter().map(|(a, b)| json!([a, b])).collect();
+                let table = json!({ "from": left, "to": right, "rows": rows });
+                format!(
+                    "{}\n",
+                    serde_json::to_string_pretty(&table).unwrap_or_default()
+                )
+            }
+        }
+    }
+}
+
+// Runs `far_cel_converter table ...` and returns the process exit code.
+pub fn run(args: &[String], registry: &Registry, locale: &Locale) -> i32 {
+    let error = |message: &str| eprintln!("{}", locale.format("error", &[("message", message)]));
+    let usage = locale.text("table-usage");
+    let mut spec = Vec::new();
+    let mut to = None;
+    let mut format = Format::Text;
+    let mut chart_file = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "--to" | "--format" | "--chart" => match args.next() {
+                Some(value) => value,
+                None => {
+                    error(&format!("{arg} needs a value\n{usage}"));
+                    return 2;
+                }
+            },
+            "--help" | "-h" => {
+                println!("{usage}");
+                return 0;
+            }
+            _ => {
+                spec.push(arg.as_str());
+                continue;
+            }
+        };
+        match arg.as_str() {
+            "--to" => match registry.find(value) {
+                Ok(unit) => to = Some(unit.clone()),
+                Err(e) => {
+                    error(&e);
+                    return 2;
+                }
+            },
+            "--format" => {
+                format = match value.as_str() {
+                    "text" => Format::Text,
+                    "csv" => Format::Csv,
+                    "markdown" | "md" => Format::Markdown,
+                    "json" => Format::Json,
+                    _ => {
+                        error(&format!("unknown format '{value}'\n{usage}"));
+                        return 2;
+                    }
+                }
+            }
+            _ => chart_file = Some(value.clone()),
+        }
+    }
+
+    let table = match Table::build(&spec.join(" "), to, registry) {
+        Ok(table) => table,
+        Err(e) => {
+            error(&e);
+            return 1;
+        }
+    };
+    print!("{}", table.render(format, locale));
+
+    if let Some(path) = chart_file {
+        let drawing = if path.ends_with(".svg") {
+            chart::svg(&table, locale)
+        } else {
+            chart::ascii(&table, locale)
+        };
+        if let Err(e) = fs::write(&path, drawing) {
+            error(&format!("cannot write {path}: {e}"));
+            return 1;
+        }
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(spec: &str) -> Result<Table, String> {
+        Table::build(spec, None, &Registry::builtin())
+    }
+
+    #[test]
+    fn rows_cover_the_range() {
+        let table = build("0C to 20C step 10").unwrap();
+        assert_eq!(
+            (table.from.symbol.as_str(), table.to.symbol.as_str()),
+            ("C", "F")
+        );
+        assert_eq!(table.rows, vec![(0.0, 32.0), (10.0, 50.0), (20.0, 68.0)]);
+        let table = build("-40F to 212F step 10").unwrap();
+        assert_eq!(table.rows.first(), Some(&(-40.0, -40.0)));
+        assert_eq!(table.rows.last(), Some(&(210.0, 98.88888888889)));
+        // The end can be written in another unit.
+        let table = build("0C to 32F step 10").unwrap();
+        assert_eq!(table.rows, vec![(0.0, 32.0)]);
+        let registry = Registry::builtin();
+        let km = registry.find("km").cloned().ok();
+        let table = Table::build("1 mi to 3 mi step 1", km, &registry).unwrap();
+        assert_eq!(table.rows[2], (3.0, 4.828032));
+    }
+
+    #[test]
+    fn steps_that_do_not_divide_the_range() {
+        let table = build("0C to 25C step 10").unwrap();
+        assert_eq!(table.rows.len(), 3);
+        assert_eq!(table.rows.last().unwrap().0, 20.0);
+        // Rows are multiples of the step, so 0.1 steps land on 0.3 exactly.
+        let table = build("0C to 0.35C step 0.1").unwrap();
+        let values: Vec<f64> = table.rows.iter().map(|r| r.0).collect();
+        assert_eq!(values, vec![0.0, 0.1, 0.2, 0.3]);
+    }
+
+    #[test]
+    fn rejects_bad_ranges() {
+        for (spec, error) in [
+            ("0C to 20C step 0", "step must be a positive number"),
+            ("0C to 20C step -5", "step must be a positive number"),
+            ("0C to 20C step inf", "step must be a positive number"),
+            ("0C to 20C step x", "step 'x' is not a number"),
+            ("20C to 0C step 5", "the range ends before it starts"),
+            (
+                "0C to 20C step 0.001",
+                "the table would have more than 10000 rows",
+            ),
+            (
+                "0C to 20C",
+                "expected '<FROM> to <END> step <STEP>', got '0C to 20C'",
+            ),
+            ("0 to 20 step 5", "'0' needs a unit"),
+            (
+                "0 m to 20 m step 5",
+                "name the unit of the second column with --to",
+            ),
+        ] {
+            assert_eq!(build(spec).err().as_deref(), Some(error), "{spec}");
+        }
+    }
+
+    #[test]
+    fn formats() {
+        let en = Locale::new("en");
+        let table = build("0C to 20C step 10").unwrap();
+        assert_eq!(
+            table.render(Format::Text, &en),
+            "    C      F\n 0.00  32.00\n10.00  50.00\n20.00  68.00\n"
+        );
+        assert_eq!(
+            table.render(Format::Csv, &en),
+            "C,F\n0.00,32.00\n10.00,50.00\n20.00,68.00\n"
+        );
+        assert_eq!(
+            table.render(Format::Markdown, &en),
+            "| C | F |\n|---:|---:|\n| 0.00 | 32.00 |\n| 10.00 | 50.00 |\n| 20.00 | 68.00 |\n"
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&table.render(Format::Json, &en)).unwrap();
+        assert_eq!(
+            json,
+            json!({ "from": "C", "to": "F", "rows": [[0.0, 32.0], [10.0, 50.0], [20.0, 68.0]] })
+        );
+    }
+
+    #[test]
+    fn people_get_the_local_decimal_separator() {
+        let pl = Locale::new("pl");
+        let table = build("0C to 1C step 0.5").unwrap();
+        assert_eq!(
+            table.render(Format::Text, &pl),
+            "   C      F\n0,00  32,00\n0,50  32,90\n1,00  33,80\n"
+        );
+        assert!(table
+            .render(Format::Markdown, &pl)
+            .contains("| 0,50 | 32,90 |"));
+        assert_eq!(
+            table.render(Format::Csv, &pl),
+            "C,F\n0.00,32.00\n0.50,32.90\n1.00,33.80\n"
+        );
+        assert!(table.render(Format::Json, &pl).contains("32.9"));
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use far_cel_core::exact;
+use far_cel_core::expr;
+use far_cel_core::units::{Dimension, Registry, Unit};
+use serde::Deserialize;
+
+fn one() -> f64 {
+    1.0
+}
+
+// One `[units.<symbol>]` table of the config file:
+//   value in `base` = value * factor + offset
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Definition {
+    // A known unit such as "in", or a compound one such as "lbf/in/in".
+    base: String,
+    #[serde(default = "one")]
+    factor: f64,
+    #[serde(default)]
+    offset: f64,
+    name: Option<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    // Expected dimension, a base name like "length" or a unit like "kg/m/s/s".
+    dimension: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    #[serde(default)]
+    units: BTreeMap<String, Definition>,
+}
+
+// Symbols of the config's own units that `base` refers to.
+fn references<'a>(base: &'a str, config: &'a Config) -> Vec<&'a str> {
+    base.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '°' || c == '-'))
+        .filter_map(|word| {
+            config
+                .units
+                .iter()
+                .find(|(symbol, def)| *symbol == word || def.aliases.iter().any(|a| a == word))
+                .map(|(symbol, _)| symbol.as_str())
+        })
+        .collect()
+}
+
+fn expected_dimension(text: &str, registry: &Registry) -> Result<Dimension, String> {
+    match Dimension::from_name(text) {
+        Some(dimension) => Ok(dimension),
+        None => expr::parse_unit(text, registry).map(|unit| unit.dimension),
+    }
+}
+
+// Adds `symbol` once every unit of the config it is built on is in the
+// registry. `path` holds the units being defined, to report cycles, and
+// `done` the ones already added.
+fn define(
+    symbol: &str,
+    config: &Config,
+    registry: &mut Registry,
+    path: &mut Vec<String>,
+    done: &mut Vec<String>,
+) -> Result<(), String> {
+    if done.iter().any(|s| s == symbol) {
+        return Ok(());
+    }
+    if let Some(at) = path.iter().position(|s| s == symbol) {
+        let mut cycle = path[at..].to_vec();
+        cycle.push(symbol.to_string());
+        return Err(format!("units defined in a cycle: {}", cycle.join(" -> ")));
+    }
+    let def = &config.units[symbol];
+    path.push(symbol.to_string());
+    for reference in references(&def.base, config) {
+        define(reference, config, registry, path, done)?;
+    }
+    path.pop();
+
+    let context = |e: String| format!("unit '{symbol}': {e}");
+    if !def.factor.is_finite() || def.factor == 0.0 || !def.offset.is_finite() {
+        return Err(context("factor must be a non-zero number".to_string()));
+    }
+    let base = expr::parse_unit(&def.base, registry).map_err(context)?;
+    // The exact scale of the base composed with the decimal factor and
+    // offset as written, so exact mode does not inherit f64 rounding.
+    let (f, o) = (
+        exact::from_f64(def.factor).map_err(context)?,
+        exact::from_f64(def.offset).map_err(context)?,
+    );
+    let (base_factor, base_offset) = exact::exact_scale(&base).map_err(context)?;
+    let unit = Unit {
+        name: def.name.clone().unwrap_or_else(|| symbol.to_string()),
+        symbol: symbol.to_string(),
+        aliases: def.aliases.clone(),
+        dimension: base.dimension,
+        factor: def.factor * base.factor,
+        offset: def.offset * base.factor + base.offset,
+        exact: Some((&f * &base_factor, o * &base_factor + base_offset)),
+    };
+
+    if let Some(text) = &def.dimension {
+        let expected = expected_dimension(text, registry).map_err(context)?;
+        if expected != unit.dimension {
+            return Err(context(format!(
+                "declared as {} but '{}' is {}",
+                expected.describe(),
+                def.base,
+                unit.dimension.describe()
+            )));
+        }
+    }
+    for name in [&unit.symbol, &unit.name].into_iter().chain(&unit.aliases) {
+        if let Some(existing) = registry.units().iter().find(|u| u.matches(name)) {
+            let problem = if existing.dimension == unit.dimension {
+                format!("'{name}' is already the unit {}", existing.name)
+            } else {
+                format!(
+                    "'{name}' is already a unit of {}, not {}",
+                    existing.dimension.describe(),
+                    unit.dimension.describe()
+                )
+            };
+            return Err(context(problem));
+        }
+    }
+    registry.add(unit);
+    done.push(symbol.to_string());
+    Ok(())
+}
+
+// Adds every unit of a config file to `registry`.
+pub fn install(text: &str, registry: &mut Registry) -> Result<usize, String> {
+    let config: Config = toml::from_str(text).map_err(|e| e.to_string())?;
+    let mut done = Vec::new();
+    for symbol in config.units.keys() {
+        define(symbol, &config, registry, &mut Vec::new(), &mut done)?;
+    }
+    Ok(config.units.len())
+}
+
+// Like the rate snapshot: --units, else $FAR_CEL_UNITS, else an optional
+// file in the home directory.
+pub fn load(registry: &mut Registry, explicit: Option<&str>) -> Result<(), String> {
+    let (path, required) = match explicit
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("FAR_CEL_UNITS").map(PathBuf::from))
+    {
+        Some(path) => (path, true),
+        None => match env::var_os("HOME") {
+            Some(home) => (
+                PathBuf::from(home).join(".far_cel_converter_units.toml"),
+                false,
+            ),
+            None => return Ok(()),
+        },
+    };
+    if !required && !path.exists() {
+        return Ok(());
+    }
+    let text =
+        fs::read_to_string(&path).map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+    install(&text, registry).map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use far_cel_core::expr::Variables;
+
+    const EXAMPLE: &str = include_str!("../tests/fixtures/units.toml");
+
+    fn registry(text: &str) -> Result<Registry, String> {
+        let mut registry = Registry::builtin();
+        install(text, &mut registry)?;
+        Ok(registry)
+    }
+
+    fn convert(registry: &Registry, input: &str) -> String {
+        let (quantity, target) = expr::evaluate(input, registry, &Variables::new()).unwrap();
+        quantity.convert_to(&target.unwrap()).unwrap().to_string()
+    }
+
+    #[test]
+    fn example_units() {
+        let registry = registry(EXAMPLE).unwrap();
+        assert_eq!(convert(&registry, "40 mil to mm"), "1.02 mm");
+        assert_eq!(convert(&registry, "2 thou to in"), "2.0000e-3 in");
+        assert_eq!(convert(&registry, "1 bbl to l"), "158.99 l");
+        assert_eq!(convert(&registry, "0 psi-gauge to psi"), "14.70 psi");
+        assert_eq!(
+            convert(&registry, "100 kPa to psi-gauge"),
+            "-0.19 psi-gauge"
+        );
+        // The offset is counted once, differences leave it out.
+        assert_eq!(
+            convert(&registry, "5 psi-gauge + 2 psi-gauge delta to psi-gauge"),
+            "7.00 psi-gauge"
+        );
+        assert_eq!(
+            convert(&registry, "2 * (5 psi-gauge - 3 psi-gauge) to psi"),
+            "4.00 psi"
+        );
+        assert!(expr::evaluate("5 psi-gauge + 5 psi-gauge", &registry, &Variables::new()).is_err());
+        assert!(expr::evaluate("2 * 5 psi-gauge", &registry, &Variables::new()).is_err());
+    }
+
+    #[test]
+    fn exact_mode_keeps_decimal_factors() {
+        let text = format!("{EXAMPLE}\n[units.palm]\nbase = \"in\"\nfactor = 0.3\n");
+        let registry = registry(&text).unwrap();
+        let exact = |entry: &str| {
+            let (value, unit) = exact::convert_entry(entry, None, None, &registry).unwrap();
+            exact::describe(&value, &unit, None, exact::Rounding::HalfEven)
+        };
+        // 0.3 of 0.0254 m, where the f64 product is 0.007619999999999999.
+        assert_eq!(exact("1 palm to m"), "0.00762 m");
+        assert_eq!(exact("1 mil to m"), "0.0000254 m");
+        assert_eq!(exact("1000 thou to in"), "1 in");
+        assert_eq!(exact("2 bbl to l"), "317.974589856 l");
+    }
+
+    #[test]
+    fn order_of_definitions_does_not_matter() {
+        let registry =
+            registry("[units.b]\nbase = \"a\"\nfactor = 2\n[units.a]\nbase = \"m\"\nfactor = 3\n")
+                .unwrap();
+        assert_eq!(convert(&registry, "1 b to m"), "6.00 m");
+    }
+
+    #[test]
+    fn rejects_cycles() {
+        let error =
+            registry("[units.a]\nbase = \"b\"\n[units.b]\nbase = \"c\"\n[units.c]\nbase = \"a\"\n")
+                .err()
+                .unwrap();
+        assert_eq!(error, "units defined in a cycle: a -> b -> c -> a");
+        let error = registry("[units.a]\nbase = \"a/s\"\n").err().unwrap();
+        assert_eq!(error, "units defined in a cycle: a -> a");
+    }
+
+    #[test]
+    fn rejects_dimension_conflicts() {
+        let error = registry("[units.st]\nbase = \"lb\"\nfactor = 14\ndimension = \"length\"\n")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error,
+            "unit 'st': declared as length (m) but 'lb' is mass (kg)"
+        );
+        let error = registry("[units.hand]\nbase = \"in\"\nfactor = 4\naliases = [\"g\"]\n")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error,
+            "unit 'hand': 'g' is already a unit of mass (kg), not length (m)"
+        );
+        assert!(registry("[units.ft]\nbase = \"in\"\nfactor = 12\n").is_err());
+        assert!(registry("[units.x]\nbase = \"parsec\"\n").is_err());
+        assert!(registry("[units.x]\nbase = \"m\"\nfactor = 0\n").is_err());
+    }
+}
@@ -0,0 +1,163 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+// A HOME of its own for each test, so rate and unit files of the machine
+// stay out of it.
+fn home(name: &str) -> PathBuf {
+    let home = env::temp_dir().join(format!(
+        "far_cel_converter_cli_{name}_{}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&home).unwrap();
+    home
+}
+
+// Runs the converter with `args` and `input` on stdin, returning the exit
+// code, stdout and stderr.
+fn run(name: &str, args: &[&str], input: impl AsRef<[u8]>) -> (i32, String, String) {
+    let home = home(name);
+    let mut child = Command::new(env!("CARGO_BIN_EXE_far_cel_converter"))
+        .args(args)
+        .env("HOME", &home)
+        .env("LANG", "en_US.UTF-8")
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .env_remove("FAR_CEL_RATES")
+        .env_remove("FAR_CEL_UNITS")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Cannot start far_cel_converter");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_ref())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    fs::remove_dir_all(&home).unwrap();
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn batch_conversions() {
+    let (code, out, err) = run("batch", &["--batch", "--from", "F"], "32\n212\n\n-40\n");
+    assert_eq!(code, 0);
+    assert_eq!(out, "0.00 C\n100.00 C\n-40.00 C\n");
+    assert_eq!(err, "");
+
+    let (code, out, _) = run("batch_to", &["--batch", "--to", "K"], "0 C\n32F\n");
+    assert_eq!(code, 0);
+    assert_eq!(out, "273.15 K\n273.15 K\n");
+}
+
+#[test]
+fn batch_errors_name_their_line() {
+    let (code, out, err) = run(
+        "batch_errors",
+        &["--batch", "--from", "C"],
+        "20\nwarm\n-300\n100\n",
+    );
+    assert_eq!(code, 1);
+    // The good lines are still converted.
+    assert_eq!(out, "68.00 F\n212.00 F\n");
+    let lines: Vec<&str> = err.lines().collect();
+    assert_eq!(lines.len(), 2, "{err}");
+    assert!(lines[0].starts_with("line 2: "), "{err}");
+    assert_eq!(lines[1], "line 3: -300.00 C is below absolute zero");
+}
+
+#[test]
+fn batch_skips_lines_that_are_not_utf8() {
+    let (code, out, err) = run(
+        "batch_utf8",
+        &["--batch", "--from", "C"],
+        b"20\n\xff\xfe\n100\n",
+    );
+    assert_eq!(code, 1);
+    assert_eq!(out, "68.00 F\n212.00 F\n");
+    assert!(err.starts_with("line 2: cannot read it: "), "{err}");
+    assert_eq!(err.lines().count(), 1, "{err}");
+}
+
+#[test]
+fn polish_tables_and_usage() {
+    let (code, out, _) = run(
+        "pl_table",
+        &["--lang", "pl", "table", "0C", "to", "1C", "step", "0.5"],
+        "",
+    );
+    assert_eq!(code, 0);
+    assert_eq!(out, "   C      F\n0,00  32,00\n0,50  32,90\n1,00  33,80\n");
+
+    let (code, _, err) = run("pl_usage", &["--lang", "pl", "--batch", "5"], "");
+    assert_eq!(code, 2);
+    assert!(err.starts_with("Błąd: --batch reads values from stdin, do not pass one\nUżycie:"));
+    assert!(err.contains("\n\nOpcje:\n"));
+
+    let (code, _, err) = run(
+        "pl_table_usage",
+        &["--lang", "pl", "table", "--format", "xml"],
+        "",
+    );
+    assert_eq!(code, 2);
+    assert!(err.starts_with("Błąd: unknown format 'xml'\nUżycie:"));
+}
+
+#[test]
+fn usage_errors() {
+    let (code, _, err) = run("batch_value", &["--batch", "5"], "");
+    assert_eq!(code, 2);
+    assert!(err.starts_with("Error: --batch reads values from stdin, do not pass one\nUsage:"));
+
+    let (code, _, err) = run("bad_option", &["5", "--frmo", "F"], "");
+    assert_eq!(code, 2);
+    assert!(err.starts_with("Error: unexpected argument '--frmo'"));
+
+    let (code, _, err) = run("sig", &["5", "--sig", "0"], "");
+    assert_eq!(code, 2);
+    assert!(err.starts_with("Error: invalid significant figures '0'"));
+}
+
+#[test]
+fn double_dash_ends_the_options() {
+    let (code, out, _) = run("double_dash", &["--to", "C", "--", "32F"], "");
+    assert_eq!(code, 0);
+    assert_eq!(out, "0.00 C\n");
+    let (code, out, _) = run("double_dash_alone", &["--", "5"], "");
+    assert_eq!(code, 0);
+    assert_eq!(out, "5.00\n");
+}
+
+#[test]
+fn help_despite_broken_files() {
+    let units = env::temp_dir().join(format!(
+        "far_cel_converter_broken_{}.toml",
+        std::process::id()
+    ));
+    fs::write(&units, "[units.x\nbase = ").unwrap();
+    let units = units.to_str().unwrap();
+
+    let (code, _, err) = run("broken", &["--units", units, "5 F"], "");
+    assert_eq!(code, 2);
+    assert!(err.starts_with("Error: "));
+    let (code, out, _) = run("broken_help", &["--units", units, "--help"], "");
+    assert_eq!(code, 0);
+    assert!(out.starts_with("Usage:"));
+    let (code, out, _) = run(
+        "broken_table_help",
+        &["--units", units, "table", "--help"],
+        "",
+    );
+    fs::remove_file(units).unwrap();
+    assert_eq!(code, 0);
+    assert!(out.contains("table"));
+}
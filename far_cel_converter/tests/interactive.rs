@@ -0,0 +1,105 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Runs the interactive mode with `input` typed on stdin and returns what it
+// printed. Each session gets its own HOME, so history and rate files of the
+// machine (or of other tests) stay out of it.
+fn session(name: &str, input: &str) -> String {
+    let home = env::temp_dir().join(format!("far_cel_converter_{name}_{}", std::process::id()));
+    fs::create_dir_all(&home).unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_far_cel_converter"))
+        .env("HOME", &home)
+        .env("LANG", "en_US.UTF-8")
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .env_remove("FAR_CEL_RATES")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Cannot start far_cel_converter");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    fs::remove_dir_all(&home).unwrap();
+    assert!(output.status.success(), "exit status {}", output.status);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn menu_conversions() {
+    let out = session("menu", "menu 1\n98.6\nmenu 2\n-40\nquit\n");
+    assert!(out.starts_with("Welcome to Farenheit/Celsius converter!"));
+    assert!(out.contains("98.60 Farenheit is equal to 37.00 Celsius"));
+    assert!(out.contains("-40.00 Celsius is equal to -40.00 Farenheit"));
+}
+
+#[test]
+fn invalid_options() {
+    let out = session("options", "menu 3\nmenu\nmenu one\n");
+    assert_eq!(
+        out.matches("Provide option menu 1 or menu 2 only!").count(),
+        3
+    );
+}
+
+#[test]
+fn non_numeric_temperatures() {
+    let out = session("not_numbers", "menu 1\nabc\nmenu 2\n\nmenu 1\n12,5.3\n");
+    assert_eq!(out.matches("Given entry is not a number!").count(), 3);
+    assert!(!out.contains("is equal to"));
+}
+
+#[test]
+fn below_absolute_zero() {
+    let out = session("absolute_zero", "menu 2\n-300\n");
+    assert!(out.contains("Error: -300.00 °C is below absolute zero"));
+}
+
+#[test]
+fn keeps_going_after_errors() {
+    let out = session("recovery", "menu 7\nmenu 1\nwarm\nbogus unit\nmenu 1\n32\n");
+    assert!(out.contains("Provide option menu 1 or menu 2 only!"));
+    assert!(out.contains("Given entry is not a number!"));
+    assert!(out.contains("Error: "));
+    assert!(out.contains("32.00 Farenheit is equal to 0.00 Celsius"));
+}
+
+#[test]
+fn bare_numbers_are_expressions() {
+    let out = session("numbers", "5\n-40\nans * 2\nx = 3\nx + 1\n");
+    assert!(out.contains("\n5.00\n"));
+    assert!(out.contains("\n-40.00\n"));
+    assert!(out.contains("\n-80.00\n"));
+    assert!(out.contains("x = 3.00"));
+    assert!(out.contains("\n4.00\n"));
+    assert!(!out.contains("Provide option"));
+}
+
+#[test]
+fn expressions_and_variables() {
+    let out = session(
+        "expressions",
+        "3 ft + 20 in to cm\nx = 10 C delta\nx in C\n",
+    );
+    assert!(out.contains("142.24 cm"));
+    assert!(out.contains("x = 18.00 F delta"));
+    assert!(out.contains("\n10.00 C delta"));
+}
+
+#[test]
+fn weather_quantities() {
+    let out = session(
+        "weather",
+        "heat-index 90F 50%\nwind-chill 15C, 2 km/h\ndew-point 25C 150%\n",
+    );
+    assert!(out.contains("94.60 F"));
+    assert!(out.contains("Warning: wind chill is defined at or below 50 °F"));
+    assert!(out.contains("Error: relative humidity must be above 0% and at most 100%"));
+}
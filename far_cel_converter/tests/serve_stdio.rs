@@ -0,0 +1,158 @@
+use std::env;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdout, Command, Stdio};
+
+use serde_json::{json, Value};
+
+// A running `--serve-stdio` process, talked to one line at a time the way an
+// editor would, waiting for each response before sending the next request.
+struct Server {
+    child: Child,
+    output: BufReader<ChildStdout>,
+}
+
+impl Server {
+    fn start() -> Server {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_far_cel_converter"))
+            .arg("--serve-stdio")
+            .env("HOME", env::temp_dir().join("far_cel_converter_no_home"))
+            .env_remove("FAR_CEL_RATES")
+            .env_remove("FAR_CEL_UNITS")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Cannot start far_cel_converter");
+        let output = BufReader::new(child.stdout.take().unwrap());
+        Server { child, output }
+    }
+
+    fn write(&mut self, line: &str) {
+        let input = self.child.stdin.as_mut().unwrap();
+        writeln!(input, "{line}").unwrap();
+        input.flush().unwrap();
+    }
+
+    fn send(&mut self, line: &str) -> Value {
+        self.write(line);
+        let mut response = String::new();
+        self.output.read_line(&mut response).unwrap();
+        serde_json::from_str(&response).unwrap()
+    }
+
+    fn call(&mut self, id: u64, method: &str, params: Value) -> Value {
+        let request = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+        let response = self.send(&request.to_string());
+        assert_eq!(response["id"], id);
+        response
+    }
+
+    // Closes stdin and checks the server exits cleanly with nothing more to say.
+    fn stop(mut self) {
+        drop(self.child.stdin.take());
+        let mut rest = String::new();
+        self.output.read_line(&mut rest).unwrap();
+        assert_eq!(rest, "");
+        assert!(self.child.wait().unwrap().success());
+    }
+}
+
+#[test]
+fn convert() {
+    let mut server = Server::start();
+    let response = server.call(1, "convert", json!({"value": 98.6, "from": "F", "to": "C"}));
+    assert_eq!(response["result"]["text"], "37.00 C");
+    assert_eq!(response["result"]["unit"], "C");
+    assert!((response["result"]["value"].as_f64().unwrap() - 37.0).abs() < 1e-9);
+
+    // Without "to" a temperature goes to the other scale.
+    let response = server.call(2, "convert", json!({"value": -40, "from": "C"}));
+    assert_eq!(response["result"]["text"], "-40.00 F");
+
+    let response = server.call(3, "convert", json!({"value": 1, "from": "mi", "to": "km"}));
+    assert_eq!(response["result"]["text"], "1.61 km");
+    server.stop();
+}
+
+#[test]
+fn parse_expression() {
+    let mut server = Server::start();
+    let response = server.call(
+        1,
+        "parse-expression",
+        json!({"expression": "3 ft + 20 in to cm"}),
+    );
+    assert_eq!(response["result"]["text"], "142.24 cm");
+    assert_eq!(response["result"]["target"], "cm");
+    assert_eq!(response["result"]["dimension"], "length (m)");
+
+    let response = server.call(2, "parse-expression", json!({"expression": "72F - 32F"}));
+    assert_eq!(response["result"]["unit"], "F delta");
+    assert_eq!(response["result"]["target"], Value::Null);
+    server.stop();
+}
+
+#[test]
+fn list_units() {
+    let mut server = Server::start();
+    let response = server.call(1, "list-units", json!({}));
+    let units = response["result"].as_array().unwrap();
+    assert!(units
+        .iter()
+        .any(|u| u["symbol"] == "ft" && u["name"] == "foot"));
+
+    let response = server.call(2, "list-units", json!({"dimension": "temperature"}));
+    let symbols: Vec<&str> = response["result"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|u| u["symbol"].as_str().unwrap())
+        .collect();
+    assert_eq!(symbols, ["K", "C", "F", "R"]);
+    server.stop();
+}
+
+#[test]
+fn errors_keep_the_server_running() {
+    let mut server = Server::start();
+    let response = server.call(1, "convert", json!({"value": -500, "from": "F", "to": "C"}));
+    assert_eq!(response["error"]["code"], -32000);
+    assert_eq!(
+        response["error"]["message"],
+        "-500.00 F is below absolute zero"
+    );
+
+    let response = server.call(2, "convert", json!({"value": "hot", "from": "F"}));
+    assert_eq!(response["error"]["code"], -32602);
+    let response = server.call(3, "translate", json!({}));
+    assert_eq!(response["error"]["code"], -32601);
+    let response = server.send("{not json");
+    assert_eq!(response["error"]["code"], -32700);
+    assert_eq!(response["id"], Value::Null);
+
+    // Notifications get no response, so the next line answers request 4.
+    server.write(r#"{"jsonrpc": "2.0", "method": "convert", "params": {"value": 1}}"#);
+    let response = server.call(4, "convert", json!({"value": 0, "from": "C", "to": "K"}));
+    assert_eq!(response["result"]["text"], "273.15 K");
+    server.stop();
+}
+
+#[test]
+fn batch() {
+    let mut server = Server::start();
+    let response = server.send(
+        &json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "convert", "params": {"value": 0, "from": "C"}},
+            {"jsonrpc": "2.0", "method": "list-units"},
+            {"jsonrpc": "2.0", "id": 2, "method": "convert", "params": {"value": 1, "from": "metr", "to": "ft"}},
+        ])
+        .to_string(),
+    );
+    let responses = response.as_array().unwrap();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["result"]["text"], "32.00 F");
+    assert_eq!(
+        responses[1]["error"]["message"],
+        "unknown unit 'metr', did you mean 'meter'?"
+    );
+    server.stop();
+}
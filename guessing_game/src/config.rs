@@ -0,0 +1,258 @@
+// Settings of one game, read from the command line.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+pub const USAGE: &str = "Usage:
+  guessing_game [--difficulty <LEVEL>] [--min <N>] [--max <N>] [--max-attempts <N>] [--time-limit <SECONDS>] [--seed <N>] [--auto | --reverse | --proximity]
+  guessing_game scores [--top <N>]   show the leaderboards and your statistics
+  guessing_game code ...             break a code of digits or colors, see code --help
+  guessing_game serve | join ...     play with others over the network, see serve --help
+
+Options:
+  --difficulty <LEVEL>  easy (1 to 50, 10 attempts), medium (1 to 100, 7 attempts)
+                        or hard (1 to 1000, 10 attempts)
+  --min <N>             smallest possible number, 1 by default
+  --max <N>             largest possible number, 100 by default
+  --max-attempts <N>    guesses allowed before the game is lost, unlimited by default
+  --time-limit <SECONDS>
+                        find the number in time, scored on guesses and time left
+  --seed <N>            pick the secret number from this seed, to replay the same game
+                        (seeded games are left out of the high scores)
+  --auto                let the computer find the number by bisection
+  --reverse             think of a number yourself and let the computer guess it
+  --proximity           answers say hot, warm or cold and whether you got warmer or colder,
+                        instead of too low or too big
+  --name <NAME>         name to record your games under, defaults to $USER
+  --scores <FILE>       high-score file, defaults to $GUESSING_GAME_SCORES
+                        or ~/.guessing_game_scores.json
+
+While guessing, type 'hint' for the best next guess and how many guesses it may still take.
+Games with hints are left out of the leaderboards.";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        match name {
+            "easy" => Some(Difficulty::Easy),
+            "medium" => Some(Difficulty::Medium),
+            "hard" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    // Easy leaves guesses to spare, medium and hard allow exactly as many as
+    // a perfect bisection needs in the worst case.
+    pub fn config(self) -> Config {
+        let (max, max_attempts) = match self {
+            Difficulty::Easy => (50, 10),
+            Difficulty::Medium => (100, 7),
+            Difficulty::Hard => (1000, 10),
+        };
+        Config {
+            min: 1,
+            max,
+            max_attempts: Some(max_attempts),
+            difficulty: Some(self),
+            seed: None,
+            auto: false,
+            reverse: false,
+            proximity: false,
+            time_limit: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub min: u32,
+    pub max: u32,
+    // None lets the player guess until they get it.
+    pub max_attempts: Option<u32>,
+    // The preset the game was started with, None once any of it is changed.
+    pub difficulty: Option<Difficulty>,
+    // Fixed seed of the random number generator, None for a fresh game.
+    pub seed: Option<u64>,
+    // The computer plays instead of the player.
+    pub auto: bool,
+    // The player thinks of the number and the computer guesses.
+    pub reverse: bool,
+    // Guesses are answered with how close they are instead of which way to go.
+    pub proximity: bool,
+    // Seconds to find the number in, None for no hurry.
+    pub time_limit: Option<u32>,
+}
+
+impl Default for Config {
+    // The original game: 1 to 100 with no limit.
+    fn default() -> Config {
+        Config {
+            min: 1,
+            max: 100,
+            max_attempts: None,
+            difficulty: None,
+            seed: None,
+            auto: false,
+            reverse: false,
+            proximity: false,
+            time_limit: None,
+        }
+    }
+}
+
+impl Config {
+    // The number to find, the same one every time for a given --seed.
+    pub fn pick_secret(&self) -> u32 {
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        rng.gen_range(self.min..=self.max)
+    }
+}
+
+pub fn number(option: &str, value: Option<&String>) -> Result<u32, String> {
+    let value = value.ok_or_else(|| format!("{option} needs a number"))?;
+    value.parse().map_err(|_| {
+        format!(
+            "{option} must be a whole number from 0 to {}, got '{value}'",
+            u32::MAX
+        )
+    })
+}
+
+// Any u64 works as a seed, unlike the other numbers.
+pub fn parse_seed(value: Option<&String>) -> Result<u64, String> {
+    let value = value.ok_or("--seed needs a number")?;
+    value
+        .parse()
+        .map_err(|_| format!("--seed must be a whole number, got '{value}'"))
+}
+
+pub fn parse_args(args: &[String]) -> Result<Config, String> {
+    // A preset fills in what the other options leave out, wherever it appears.
+    let mut difficulty = None;
+    let (mut min, mut max, mut max_attempts) = (None, None, None);
+    let (mut seed, mut time_limit) = (None, None);
+    let (mut auto, mut reverse, mut proximity) = (false, false, false);
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--difficulty" => {
+                let name = args.next().ok_or("--difficulty needs a level")?;
+                difficulty = Some(Difficulty::from_name(name).ok_or_else(|| {
+                    format!("unknown difficulty '{name}', pick easy, medium or hard")
+                })?);
+            }
+            "--min" => min = Some(number(arg, args.next())?),
+            "--max" => max = Some(number(arg, args.next())?),
+            "--max-attempts" => max_attempts = Some(number(arg, args.next())?),
+            "--time-limit" => time_limit = Some(number(arg, args.next())?),
+            "--auto" => auto = true,
+            "--reverse" => reverse = true,
+            "--proximity" => proximity = true,
+            "--seed" => seed = Some(parse_seed(args.next())?),
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+
+    let mut config = match difficulty {
+        Some(difficulty) => difficulty.config(),
+        None => Config::default(),
+    };
+    if min.is_some() || max.is_some() || max_attempts.is_some() {
+        config.difficulty = None;
+    }
+    config.min = min.unwrap_or(config.min);
+    config.max = max.unwrap_or(config.max);
+    config.max_attempts = max_attempts.or(config.max_attempts);
+    config.seed = seed;
+    config.auto = auto;
+    config.reverse = reverse;
+    config.proximity = proximity;
+    config.time_limit = time_limit;
+
+    if config.min >= config.max {
+        return Err(format!(
+            "--min ({}) must be smaller than --max ({})",
+            config.min, config.max
+        ));
+    }
+    if config.auto && config.reverse {
+        return Err("--auto and --reverse cannot be used together".to_string());
+    }
+    if config.proximity && (config.auto || config.reverse) {
+        return Err(
+            "--proximity is for your own guesses, not with --auto or --reverse".to_string(),
+        );
+    }
+    if config.time_limit.is_some() && (config.auto || config.reverse) {
+        return Err(
+            "--time-limit is for your own guesses, not with --auto or --reverse".to_string(),
+        );
+    }
+    if config.time_limit == Some(0) {
+        return Err("--time-limit must be at least 1 second".to_string());
+    }
+    if config.max_attempts == Some(0) {
+        return Err("--max-attempts must be at least 1".to_string());
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Config, String> {
+        let args: Vec<String> = args.split_whitespace().map(String::from).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn presets_and_overrides() {
+        assert_eq!(parse(""), Ok(Config::default()));
+        assert_eq!(parse("--difficulty hard"), Ok(Difficulty::Hard.config()));
+        let config = parse("--max-attempts 3 --difficulty easy").unwrap();
+        assert_eq!((config.min, config.max), (1, 50));
+        assert_eq!(config.max_attempts, Some(3));
+        assert_eq!(config.difficulty, None);
+        let config = parse("--seed 7 --difficulty medium").unwrap();
+        assert_eq!(config.seed, Some(7));
+        assert_eq!(config.difficulty, Some(Difficulty::Medium));
+    }
+
+    #[test]
+    fn rejects_invalid_configurations() {
+        assert_eq!(
+            parse("--min 10 --max 5"),
+            Err("--min (10) must be smaller than --max (5)".to_string())
+        );
+        assert!(parse("--min 7 --max 7").is_err());
+        assert!(parse("--max-attempts 0").is_err());
+        assert!(parse("--max -1").is_err());
+        assert!(parse("--difficulty nightmare").is_err());
+        assert!(parse("--min").is_err());
+        assert!(parse("--lives 3").is_err());
+        assert!(parse("--seed x").is_err());
+        assert!(parse("--auto --reverse").is_err());
+        assert!(parse("--proximity --reverse").is_err());
+        assert!(parse("--time-limit 30 --auto").is_err());
+        assert!(parse("--time-limit 0").is_err());
+    }
+}
@@ -0,0 +1,442 @@
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+use std::num::IntErrorKind;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::solver::Bisection;
+use crate::timer::{self, Clock, SystemClock};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Won,
+    Lost,
+    // The input ended before the game did.
+    Abandoned,
+    // The player typed "quit".
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub outcome: Outcome,
+    pub attempts: u32,
+    pub secret: u32,
+    // Times the player asked for a hint.
+    pub hints: u32,
+    // Points of a won --time-limit challenge.
+    pub score: Option<u32>,
+}
+
+// Reads one guess, explaining what is wrong with it otherwise.
+pub fn parse_guess(text: &str, config: &Config) -> Result<u32, String> {
+    let range = format!("the number is between {} and {}", config.min, config.max);
+    let guess: u32 = match text.parse() {
+        Ok(num) => num,
+        Err(e) => {
+            let digits = text.strip_prefix('-').unwrap_or(text);
+            return Err(match e.kind() {
+                IntErrorKind::Empty => "Please type a number.".to_string(),
+                _ if text.starts_with('-') && digits.chars().all(|c| c.is_ascii_digit()) => {
+                    format!("{text} is negative, {range}.")
+                }
+                IntErrorKind::PosOverflow => format!("{text} is far too big, {range}."),
+                _ => format!("'{text}' is not a number, type a whole number like 42."),
+            });
+        }
+    };
+    if guess < config.min || guess > config.max {
+        return Err(format!("{guess} is out of range, {range}."));
+    }
+    Ok(guess)
+}
+
+// Distances from the secret that count as hot and warm. They grow with the
+// range: 5% and 20% of its size, so 1 to 100 is hot within 5.
+pub fn thresholds(config: &Config) -> (u32, u32) {
+    let size = u64::from(config.max - config.min) + 1;
+    let hot = (size / 20).max(1);
+    let warm = (size / 5).max(hot + 1);
+    (hot as u32, warm as u32)
+}
+
+// The --proximity answer to a wrong guess, compared with the previous one.
+pub fn proximity(config: &Config, secret: u32, guess: u32, previous: Option<u32>) -> String {
+    let (hot, warm) = thresholds(config);
+    let distance = guess.abs_diff(secret);
+    let feel = if distance <= hot {
+        "Hot"
+    } else if distance <= warm {
+        "Warm"
+    } else {
+        "Cold"
+    };
+    let Some(previous) = previous else {
+        return format!("{feel}!");
+    };
+    let trend = match distance.cmp(&previous.abs_diff(secret)) {
+        Ordering::Less => "warmer than",
+        Ordering::Greater => "colder than",
+        Ordering::Equal => "as close as",
+    };
+    format!("{feel}, {trend} {previous}.")
+}
+
+// Plays one game against `secret`, reading guesses from `input` and writing
+// everything the player sees to `output`, so a test can script both sides.
+pub fn play(
+    config: &Config,
+    secret: u32,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<Summary> {
+    play_with_clock(config, secret, &SystemClock::start(), input, output)
+}
+
+// `play` timing a --time-limit challenge on `clock`.
+pub fn play_with_clock(
+    config: &Config,
+    secret: u32,
+    clock: &impl Clock,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<Summary> {
+    writeln!(
+        output,
+        "Welcome to the guessing game!\nPick a number from {} to {}!",
+        config.min, config.max
+    )?;
+    if let Some(difficulty) = config.difficulty {
+        writeln!(output, "Difficulty: {}.", difficulty.name())?;
+    }
+    if let Some(max_attempts) = config.max_attempts {
+        writeln!(output, "You have {max_attempts} attempts.")?;
+    }
+    let limit = config.time_limit.map(|s| Duration::from_secs(u64::from(s)));
+    if let Some(seconds) = config.time_limit {
+        writeln!(output, "You have {seconds} seconds, the clock is running!")?;
+    }
+    if config.proximity {
+        let (hot, warm) = thresholds(config);
+        writeln!(
+            output,
+            "Hot means within {hot} of the number, warm within {warm}.\nPlease input your guess."
+        )?;
+    } else {
+        writeln!(
+            output,
+            "Please input your guess, or 'hint' for a suggestion."
+        )?;
+    }
+
+    let mut summary = Summary {
+        outcome: Outcome::Abandoned,
+        attempts: 0,
+        secret,
+        hints: 0,
+        score: None,
+    };
+    // What the answers so far tell about the number, for hints.
+    let mut bisection = Bisection::new(config.min, config.max);
+    let mut guesses: Vec<u32> = Vec::new();
+    // The last counted guess, which --proximity compares the next one with.
+    let mut previous: Option<u32> = None;
+    loop {
+        let mut guess = String::new();
+        if input.read_line(&mut guess)? == 0 {
+            // the input was closed, nobody is left to guess
+            return Ok(summary);
+        }
+
+        // the clock cannot stop a player in the middle of typing, so a guess
+        // that comes in late is simply not taken any more
+        let elapsed = clock.elapsed();
+        if limit.is_some_and(|limit| elapsed >= limit) {
+            writeln!(output, "Time's up, you lose! The number was {secret}.")?;
+            summary.outcome = Outcome::Lost;
+            return Ok(summary);
+        }
+
+        match guess.trim() {
+            "hint" if config.proximity => {
+                // the hints would give away which way to go
+                writeln!(output, "No hints in this mode, follow the warmth.")?;
+                output.flush()?;
+                continue;
+            }
+            "hint" => {
+                // a hint costs no attempt, but keeps the game off the leaderboards
+                summary.hints += 1;
+                writeln!(output, "{}", bisection.hint())?;
+                output.flush()?;
+                continue;
+            }
+            "quit" | "exit" => {
+                let list: Vec<String> = guesses.iter().map(|g| g.to_string()).collect();
+                let tried = match guesses.len() {
+                    0 => "no guesses".to_string(),
+                    1 => format!("1 guess ({})", list[0]),
+                    n => format!("{n} guesses ({})", list.join(", ")),
+                };
+                writeln!(
+                    output,
+                    "You gave up after {tried}. The number was {secret}."
+                )?;
+                summary.outcome = Outcome::Quit;
+                return Ok(summary);
+            }
+            _ => {}
+        }
+
+        let guess: u32 = match parse_guess(guess.trim(), config) {
+            Ok(num) => num,
+            Err(message) => {
+                // nothing counts as an attempt until it is a possible number
+                writeln!(output, "{message}")?;
+                output.flush()?;
+                continue;
+            }
+        };
+        // a repeated guess tells nothing new, so it costs no attempt
+        if guesses.contains(&guess) {
+            writeln!(output, "You already guessed {guess}, try another number.")?;
+            output.flush()?;
+            continue;
+        }
+        guesses.push(guess);
+        summary.attempts += 1;
+
+        let ordering = guess.cmp(&secret);
+        bisection.answer(guess, ordering);
+        match ordering {
+            Ordering::Equal => {
+                writeln!(output, "You've guessed the number, bravo!!!")?;
+                if let Some(limit) = limit {
+                    let score = timer::score(summary.attempts, elapsed, limit);
+                    summary.score = Some(score);
+                    writeln!(
+                        output,
+                        "Score: {score} ({} in {:.1} seconds).",
+                        match summary.attempts {
+                            1 => "1 guess".to_string(),
+                            n => format!("{n} guesses"),
+                        },
+                        elapsed.as_secs_f64()
+                    )?;
+                }
+                summary.outcome = Outcome::Won;
+                return Ok(summary);
+            }
+            _ if config.proximity => {
+                writeln!(output, "{}", proximity(config, secret, guess, previous))?
+            }
+            Ordering::Less => writeln!(output, "Your guess is too low!")?,
+            Ordering::Greater => writeln!(output, "Your guess is too big!")?,
+        }
+        previous = Some(guess);
+
+        if let Some(max_attempts) = config.max_attempts {
+            if summary.attempts >= max_attempts {
+                writeln!(
+                    output,
+                    "Out of attempts, you lose! The number was {secret}."
+                )?;
+                summary.outcome = Outcome::Lost;
+                return Ok(summary);
+            }
+            match max_attempts - summary.attempts {
+                1 => writeln!(output, "1 attempt left.")?,
+                left => writeln!(output, "{left} attempts left.")?,
+            }
+        }
+        if let Some(limit) = limit {
+            writeln!(output, "{}", timer::left(elapsed, limit))?;
+        }
+        // the player waits for the answer before typing the next guess
+        output.flush()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(config: &Config, secret: u32, input: &str) -> (Summary, String) {
+        let mut output = Vec::new();
+        let summary = play(config, secret, &mut input.as_bytes(), &mut output).unwrap();
+        (summary, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn win_after_hints() {
+        let (summary, output) = run(&Config::default(), 42, "50\nabc\n25\n42\n99\n");
+        assert_eq!(summary.outcome, Outcome::Won);
+        assert_eq!(summary.attempts, 3);
+        assert!(output.contains(
+            "Your guess is too big!\n'abc' is not a number, type a whole number like 42.\nYour guess is too low!\nYou've guessed"
+        ));
+    }
+
+    #[test]
+    fn loss_reveals_the_number() {
+        let config = Config {
+            max_attempts: Some(2),
+            ..Config::default()
+        };
+        let (summary, output) = run(&config, 42, "1\n2\n42\n");
+        assert_eq!(summary.outcome, Outcome::Lost);
+        assert_eq!(summary.attempts, 2);
+        assert!(output.contains("1 attempt left."));
+        assert!(output.ends_with("Out of attempts, you lose! The number was 42.\n"));
+    }
+
+    #[test]
+    fn hints_narrow_down() {
+        let (summary, output) = run(&Config::default(), 42, "hint\n50\nhint\n25\nhint\n42\n");
+        assert_eq!(summary.attempts, 3);
+        assert_eq!(summary.hints, 3);
+        assert!(output.contains("Try 50, 7 more guesses"));
+        assert!(output.contains("between 1 and 49. Try 25, 6 more guesses"));
+        assert!(output.contains("between 26 and 49. Try 37, 5 more guesses"));
+    }
+
+    #[test]
+    fn explains_bad_guesses() {
+        let config = Config::default();
+        assert_eq!(parse_guess("42", &config), Ok(42));
+        assert_eq!(
+            parse_guess("abc", &config),
+            Err("'abc' is not a number, type a whole number like 42.".to_string())
+        );
+        assert_eq!(
+            parse_guess("-5", &config),
+            Err("-5 is negative, the number is between 1 and 100.".to_string())
+        );
+        assert_eq!(
+            parse_guess("-99999999999", &config),
+            Err("-99999999999 is negative, the number is between 1 and 100.".to_string())
+        );
+        assert_eq!(
+            parse_guess("99999999999", &config),
+            Err("99999999999 is far too big, the number is between 1 and 100.".to_string())
+        );
+        assert_eq!(
+            parse_guess("0", &config),
+            Err("0 is out of range, the number is between 1 and 100.".to_string())
+        );
+        assert!(parse_guess("500", &config).is_err());
+        assert!(parse_guess("4.5", &config).is_err());
+        assert!(parse_guess("-", &config).is_err());
+        assert_eq!(
+            parse_guess("", &config),
+            Err("Please type a number.".to_string())
+        );
+    }
+
+    #[test]
+    fn only_new_guesses_in_range_count() {
+        let config = Config {
+            max_attempts: Some(3),
+            ..Config::default()
+        };
+        let (summary, output) = run(&config, 42, "50\n50\n500\n0\n-3\nten\n25\n42\n");
+        assert_eq!(summary.outcome, Outcome::Won);
+        assert_eq!(summary.attempts, 3);
+        assert!(output.contains("You already guessed 50, try another number."));
+        assert!(output.contains("500 is out of range"));
+    }
+
+    #[test]
+    fn quit_prints_a_summary() {
+        let (summary, output) = run(&Config::default(), 42, "50\n25\nquit\n42\n");
+        assert_eq!(summary.outcome, Outcome::Quit);
+        assert_eq!(summary.attempts, 2);
+        assert!(output.ends_with("You gave up after 2 guesses (50, 25). The number was 42.\n"));
+        let (_, output) = run(&Config::default(), 42, "quit\n");
+        assert!(output.ends_with("You gave up after no guesses. The number was 42.\n"));
+    }
+
+    #[test]
+    fn proximity_thresholds_scale() {
+        assert_eq!(thresholds(&Config::default()), (5, 20));
+        let config = Config {
+            min: 0,
+            max: 9,
+            ..Config::default()
+        };
+        assert_eq!(thresholds(&config), (1, 2));
+        let config = Config {
+            min: 0,
+            max: u32::MAX,
+            ..Config::default()
+        };
+        assert_eq!(thresholds(&config), (214_748_364, 858_993_459));
+    }
+
+    #[test]
+    fn warmer_and_colder() {
+        let config = Config {
+            proximity: true,
+            ..Config::default()
+        };
+        let (summary, output) = run(&config, 42, "90\n60\nhint\n30\n45\n39\n42\n");
+        assert_eq!(summary.outcome, Outcome::Won);
+        assert_eq!(summary.attempts, 6);
+        assert!(output.contains("Hot means within 5 of the number, warm within 20."));
+        assert!(output.contains(
+            "Cold!\nWarm, warmer than 90.\nNo hints in this mode, follow the warmth.\nWarm, warmer than 60.\nHot, warmer than 30.\nHot, as close as 45.\nYou've guessed"
+        ));
+        assert!(!output.contains("too"));
+    }
+
+    #[test]
+    fn timed_challenge() {
+        let config = Config {
+            time_limit: Some(60),
+            ..Config::default()
+        };
+        // Every guess takes the player 10 seconds.
+        let clock = timer::StepClock::new(Duration::from_secs(10));
+        let mut output = Vec::new();
+        let summary = play_with_clock(
+            &config,
+            42,
+            &clock,
+            &mut "50\n25\n42\n".as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(summary.outcome, Outcome::Won);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("You have 60 seconds, the clock is running!"));
+        assert!(output.contains(
+            "too big!\n50.0 seconds left.\nYour guess is too low!\n40.0 seconds left.\n"
+        ));
+        assert!(output.ends_with("Score: 633 (3 guesses in 30.0 seconds).\n"));
+        assert_eq!(summary.score, Some(633));
+
+        let clock = timer::StepClock::new(Duration::from_secs(25));
+        let mut output = Vec::new();
+        let summary = play_with_clock(
+            &config,
+            42,
+            &clock,
+            &mut "50\n25\n42\n".as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(summary.outcome, Outcome::Lost);
+        assert_eq!(summary.score, None);
+        assert_eq!(summary.attempts, 2);
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .ends_with("10.0 seconds left.\nTime's up, you lose! The number was 42.\n"));
+    }
+
+    #[test]
+    fn input_ends_early() {
+        let (summary, _) = run(&Config::default(), 42, "10\n");
+        assert_eq!(summary.outcome, Outcome::Abandoned);
+        assert_eq!(summary.attempts, 1);
+    }
+}
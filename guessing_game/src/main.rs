@@ -1,53 +1,100 @@
-use rand::Rng;
-// importing crate and range function - NOTE!!! dependency in cargo.toml file
+// the rand crate is used by config.rs - NOTE!!! dependency in cargo.toml file
 // importing from crates.io the latest version => run 'cargo build'
 // 'cargo update' updates the semantic version identifier in the dependencies
-use std::cmp::Ordering;
+use std::env;
 use std::io;
+use std::process;
+use std::time::Instant;
+
+mod config;
+mod game;
+mod mastermind;
+mod multiplayer;
+mod reverse;
+mod scores;
+mod solver;
+mod timer;
+
+// Removes an option such as `--name <NAME>` from the arguments, for the ones
+// that apply to the game and the `scores` command alike.
+fn take_option(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let at = args.iter().position(|a| a == name)?;
+    args.remove(at);
+    (at < args.len()).then(|| args.remove(at))
+}
 
 fn main() {
-    println!("Welcome to the guessing game!\nPick a number from 1 to 100!");
-    // division of lines is possible with \n
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let name = scores::player(take_option(&mut args, "--name"));
+    let scores_path = scores::path(take_option(&mut args, "--scores").as_deref());
+    match args.first().map(String::as_str) {
+        Some("scores") => process::exit(scores::run(&args[1..], &scores_path, &name)),
+        Some("code") => process::exit(mastermind::run(&args[1..])),
+        Some("serve") => process::exit(multiplayer::run_server(&args[1..])),
+        Some("join") => process::exit(multiplayer::run_client(&args[1..], &name)),
+        _ => {}
+    }
+
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{}", config::USAGE);
+        return;
+    }
+    let config = match config::parse_args(&args) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {e}\n{}", config::USAGE);
+            process::exit(2);
+        }
+    };
+
+    if config.reverse {
+        // There is no secret to pick, the player has it.
+        let played = reverse::play(&config, &mut io::stdin().lock(), &mut io::stdout().lock());
+        if let Err(e) = played {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        }
+        return;
+    }
 
-    let secret_number = rand::thread_rng().gen_range(1..=100);
-    // defining secret_number as a random number (within the specified range) generatingcargo doc --open method
-    // of a thread_rng function associated with rand crate
+    let secret_number = config.pick_secret();
+    // defining secret_number as a random number (within the specified range) generated by
+    // the gen_range method of the seeded generator, the same seed gives the same number
     // run 'cargo doc --open' to read documentation on the functions / methods
 
-    // println!("Thank you!\nYou entered {guess}Random{secret_number}");
-    // // entering an object to printed line with {} brackets
-
-    println!("Please input your guess.");
-
-    loop {
-        let mut guess = String::new();
-        // defining by 'let' a mutable variable 'guess'
-        // bound (=) to the new() is associated function of a string type
-
-        io::stdin()
-            .read_line(&mut guess)
-            .expect("Failed to read the line");
-        // calling a function of standard input, imported from the standard library
-        // reading the entry and assigning entered value to a guess mutable variable (of a String type)
-        // & - reference; read_line returns a Result -> enumeration, that has states (variants)
-        // error handling, printing error message
-
-        let guess: u32 = match guess.trim().parse() {
-            Ok(num) => num,
-            Err(_) => continue,
-        };
-        // casting guess into the unsigned 32-bit with error handler 'parse()'
-        // trim is removing from string all \n\r -> enters and spaces
-        // if OK variant
-        // '_' captures any given symbol provided by the user, to return back to the beginning
-
-        match guess.cmp(&secret_number) {
-            Ordering::Less => println!("Your guess is too low!"),
-            Ordering::Greater => println!("Your guess is too big!"),
-            Ordering::Equal => {
-                println!("You've guessed the number, bravo!!!");
-                break;
-            }
+    if config.auto {
+        // The computer's games stay out of the high scores.
+        if let Err(e) = solver::play(&config, secret_number, &mut io::stdout().lock()) {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        }
+        return;
+    }
+
+    let started = Instant::now();
+    let summary = match game::play(
+        &config,
+        secret_number,
+        &mut io::stdin().lock(),
+        &mut io::stdout().lock(),
+    ) {
+        Ok(summary) => summary,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        }
+    };
+
+    if config.seed.is_some() {
+        // Whoever knows the seed knows the number, so replays stay out of
+        // the high scores like the computer's games.
+        return;
+    }
+    let seconds = started.elapsed().as_secs_f64();
+    if let Some(record) = scores::Record::new(&name, &config, &summary, seconds) {
+        // Losing the score is no reason to fail the game that was just played.
+        if let Err(e) = scores::add(&scores_path, record) {
+            eprintln!("Could not save the score: {e}");
         }
     }
 }
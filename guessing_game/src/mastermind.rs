@@ -0,0 +1,580 @@
+use std::io::{self, BufRead, Write};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::config;
+use crate::game::Outcome;
+
+pub const USAGE: &str = "Usage:
+  guessing_game code [--length <N>] [--colors] [--duplicates | --no-duplicates]
+                     [--max-attempts <N>] [--seed <N>] [--auto]
+
+Break a secret code. After each guess you are told how many symbols are
+bulls (right symbol, right place) and cows (right symbol, wrong place).
+
+Options:
+  --length <N>          symbols in the code, 4 by default
+  --colors              use the colors R G B Y O P (Mastermind) instead of the digits 0-9
+  --duplicates          a symbol may appear more than once, the default with --colors
+  --no-duplicates       every symbol is different, the default with digits (Bulls and Cows)
+  --max-attempts <N>    guesses allowed before the game is lost, unlimited by default,
+                        not with --auto
+  --seed <N>            pick the code from this seed, to replay the same game
+  --auto                let Knuth's minimax solver break the code";
+
+const DIGITS: &str = "0123456789";
+const COLORS: &str = "RGBYOP";
+
+// The solver looks at every possible code for every guess, which is only
+// quick enough for a limited number of them.
+const SOLVER_LIMIT: usize = 10_000;
+
+// A code as positions in the alphabet of its rules.
+pub type Code = Vec<u8>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rules {
+    pub symbols: Vec<char>,
+    pub length: usize,
+    pub duplicates: bool,
+}
+
+impl Rules {
+    pub fn new(symbols: &str, length: usize, duplicates: bool) -> Result<Rules, String> {
+        let symbols: Vec<char> = symbols.chars().collect();
+        if length == 0 {
+            return Err("--length must be at least 1".to_string());
+        }
+        if !duplicates && length > symbols.len() {
+            return Err(format!(
+                "a code of {length} different symbols needs more than the {} there are",
+                symbols.len()
+            ));
+        }
+        if length > 20 {
+            return Err("--length must be at most 20".to_string());
+        }
+        Ok(Rules {
+            symbols,
+            length,
+            duplicates,
+        })
+    }
+
+    // How many codes there are, saturating for very long ones.
+    pub fn count(&self) -> usize {
+        let n = self.symbols.len();
+        (0..self.length).fold(1usize, |count, i| {
+            let choices = if self.duplicates { n } else { n - i };
+            count.saturating_mul(choices)
+        })
+    }
+
+    // Every possible code, in order.
+    pub fn all_codes(&self) -> Vec<Code> {
+        let mut codes = vec![Vec::new()];
+        for _ in 0..self.length {
+            let mut longer = Vec::new();
+            for code in &codes {
+                for symbol in 0..self.symbols.len() as u8 {
+                    if self.duplicates || !code.contains(&symbol) {
+                        let mut next = code.clone();
+                        next.push(symbol);
+                        longer.push(next);
+                    }
+                }
+            }
+            codes = longer;
+        }
+        codes
+    }
+
+    pub fn random(&self, rng: &mut impl Rng) -> Code {
+        let symbols: Vec<u8> = (0..self.symbols.len() as u8).collect();
+        if self.duplicates {
+            (0..self.length)
+                .map(|_| *symbols.choose(rng).unwrap())
+                .collect()
+        } else {
+            symbols.choose_multiple(rng, self.length).copied().collect()
+        }
+    }
+
+    // Reads a guess such as "0417" or "rgby".
+    pub fn parse(&self, text: &str) -> Result<Code, String> {
+        let mut code = Code::new();
+        for c in text.chars().filter(|c| !c.is_whitespace()) {
+            let c = c.to_ascii_uppercase();
+            match self.symbols.iter().position(|s| *s == c) {
+                Some(at) => code.push(at as u8),
+                None => {
+                    return Err(format!(
+                        "'{c}' is not one of the symbols {}",
+                        self.show(&(0..self.symbols.len() as u8).collect::<Vec<_>>())
+                    ))
+                }
+            }
+        }
+        if code.len() != self.length {
+            return Err(format!(
+                "the code has {} symbols, you typed {}",
+                self.length,
+                code.len()
+            ));
+        }
+        if !self.duplicates {
+            let repeated = code
+                .iter()
+                .enumerate()
+                .find(|(at, s)| code[..*at].contains(s));
+            if let Some((_, symbol)) = repeated {
+                return Err(format!(
+                    "every symbol of the code is different, '{}' appears twice",
+                    self.symbols[*symbol as usize]
+                ));
+            }
+        }
+        Ok(code)
+    }
+
+    pub fn show(&self, code: &[u8]) -> String {
+        code.iter().map(|s| self.symbols[*s as usize]).collect()
+    }
+}
+
+// Bulls (right symbol in the right place) and cows (right symbol in the
+// wrong place) of `guess` against `secret`.
+pub fn score(secret: &[u8], guess: &[u8]) -> (usize, usize) {
+    let bulls = secret.iter().zip(guess).filter(|(s, g)| s == g).count();
+    let mut counts = [0i32; 256];
+    for s in secret {
+        counts[*s as usize] += 1;
+    }
+    let mut common = 0;
+    for g in guess {
+        if counts[*g as usize] > 0 {
+            counts[*g as usize] -= 1;
+            common += 1;
+        }
+    }
+    (bulls, common - bulls)
+}
+
+fn plural(n: impl std::fmt::Display + PartialEq<u32>, one: &str, many: &str) -> String {
+    format!("{n} {}", if n == 1 { one } else { many })
+}
+
+fn describe((bulls, cows): (usize, usize)) -> String {
+    format!(
+        "{}, {}",
+        plural(bulls as u32, "bull", "bulls"),
+        plural(cows as u32, "cow", "cows")
+    )
+}
+
+// Knuth's minimax strategy: guess the code whose worst answer leaves the
+// fewest codes possible, preferring codes that may still be the secret.
+pub struct Solver {
+    length: usize,
+    all: Vec<Code>,
+    pub candidates: Vec<Code>,
+}
+
+impl Solver {
+    // Whether the solver is quick enough for these rules.
+    pub fn check(rules: &Rules) -> Result<(), String> {
+        if rules.count() > SOLVER_LIMIT {
+            return Err(format!(
+                "the solver handles up to {SOLVER_LIMIT} codes, these rules have {}",
+                rules.count()
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn new(rules: &Rules) -> Solver {
+        let all = rules.all_codes();
+        Solver {
+            length: rules.length,
+            candidates: all.clone(),
+            all,
+        }
+    }
+
+    pub fn next_guess(&self) -> Option<Code> {
+        match self.candidates.len() {
+            0 => return None,
+            1 | 2 => return Some(self.candidates[0].clone()),
+            _ => {}
+        }
+        // Answers as bulls * (length + 1) + cows.
+        let size = (self.length + 1) * (self.length + 1);
+        let mut best: Option<(usize, bool, &Code)> = None;
+        // While every code is still possible, codes that only differ by a
+        // renaming of the symbols are equally good, so the first guess only
+        // tries one of each kind: 0000, 0001, 0010, 0011, 0012, ...
+        let first = self.candidates.len() == self.all.len();
+        let canonical = |code: &Code| {
+            let mut next = 0;
+            code.iter().all(|s| {
+                let new_or_seen = *s <= next;
+                next = next.max(*s + 1);
+                new_or_seen
+            })
+        };
+        for guess in self.all.iter().filter(|g| !first || canonical(g)) {
+            let mut partitions = vec![0usize; size];
+            for candidate in &self.candidates {
+                let (bulls, cows) = score(candidate, guess);
+                partitions[bulls * (self.length + 1) + cows] += 1;
+            }
+            let worst = partitions.into_iter().max().unwrap_or(0);
+            let better = match best {
+                None => true,
+                Some((best_worst, best_possible, _)) => {
+                    worst < best_worst
+                        || (worst == best_worst
+                            && !best_possible
+                            && self.candidates.contains(guess))
+                }
+            };
+            if better {
+                best = Some((worst, self.candidates.contains(guess), guess));
+            }
+        }
+        best.map(|(_, _, guess)| guess.clone())
+    }
+
+    // Keeps the codes that would have given the same answer to `guess`.
+    pub fn answer(&mut self, guess: &[u8], answer: (usize, usize)) {
+        self.candidates.retain(|c| score(c, guess) == answer);
+    }
+}
+
+// `solver` breaks `secret`, printing its guesses. Returns how many it took,
+// None if no code fits the answers.
+pub fn solve(rules: &Rules, secret: &[u8], output: &mut impl Write) -> io::Result<Option<u32>> {
+    let mut solver = Solver::new(rules);
+    let mut guesses = 0;
+    while let Some(guess) = solver.next_guess() {
+        guesses += 1;
+        let answer = score(secret, &guess);
+        solver.answer(&guess, answer);
+        writeln!(
+            output,
+            "Guess {guesses}: {} -> {} ({} left)",
+            rules.show(&guess),
+            describe(answer),
+            plural(solver.candidates.len() as u32, "code", "codes")
+        )?;
+        if answer.0 == rules.length {
+            writeln!(
+                output,
+                "Broke the code in {}.",
+                plural(guesses, "guess", "guesses")
+            )?;
+            return Ok(Some(guesses));
+        }
+    }
+    writeln!(output, "No code fits the answers.")?;
+    Ok(None)
+}
+
+// Plays one code-breaking game against `secret`.
+pub fn play(
+    rules: &Rules,
+    secret: &[u8],
+    max_attempts: Option<u32>,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<(Outcome, u32)> {
+    let policy = if rules.duplicates {
+        "symbols may repeat"
+    } else {
+        "every symbol is different"
+    };
+    writeln!(
+        output,
+        "Break the code of {} symbols from {}, {policy}.",
+        rules.length,
+        rules.symbols.iter().collect::<String>()
+    )?;
+    if let Some(max_attempts) = max_attempts {
+        writeln!(output, "You have {max_attempts} attempts.")?;
+    }
+
+    let mut attempts = 0;
+    loop {
+        output.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok((Outcome::Abandoned, attempts));
+        }
+        if line.trim() == "quit" {
+            writeln!(output, "You gave up. The code was {}.", rules.show(secret))?;
+            return Ok((Outcome::Quit, attempts));
+        }
+        let guess = match rules.parse(&line) {
+            Ok(guess) => guess,
+            Err(e) => {
+                writeln!(output, "{e}")?;
+                continue;
+            }
+        };
+        attempts += 1;
+        let answer = score(secret, &guess);
+        if answer.0 == rules.length {
+            writeln!(
+                output,
+                "You broke the code in {}, bravo!!!",
+                plural(attempts, "guess", "guesses")
+            )?;
+            return Ok((Outcome::Won, attempts));
+        }
+        writeln!(output, "{}", describe(answer))?;
+        if max_attempts == Some(attempts) {
+            writeln!(
+                output,
+                "Out of attempts, you lose! The code was {}.",
+                rules.show(secret)
+            )?;
+            return Ok((Outcome::Lost, attempts));
+        }
+    }
+}
+
+// Settings of one code-breaking game, read from the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub rules: Rules,
+    pub max_attempts: Option<u32>,
+    pub seed: Option<u64>,
+    pub auto: bool,
+}
+
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut length = 4;
+    let mut colors = false;
+    let mut duplicates = None;
+    let mut max_attempts = None;
+    let mut seed = None;
+    let mut auto = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--length" => length = config::number(arg, args.next())? as usize,
+            "--colors" => colors = true,
+            "--duplicates" => duplicates = Some(true),
+            "--no-duplicates" => duplicates = Some(false),
+            "--max-attempts" => max_attempts = Some(config::number(arg, args.next())?),
+            "--seed" => seed = Some(config::parse_seed(args.next())?),
+            "--auto" => auto = true,
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+    if max_attempts == Some(0) {
+        return Err("--max-attempts must be at least 1".to_string());
+    }
+    // The solver plays until it breaks the code, it has no attempts to run out of.
+    if auto && max_attempts.is_some() {
+        return Err("--max-attempts cannot be used with --auto".to_string());
+    }
+    // Digits make Bulls and Cows, colors make Mastermind, each with its own
+    // usual policy on repeated symbols.
+    let symbols = if colors { COLORS } else { DIGITS };
+    let rules = Rules::new(symbols, length, duplicates.unwrap_or(colors))?;
+    if auto {
+        Solver::check(&rules)?;
+    }
+    Ok(Options {
+        rules,
+        max_attempts,
+        seed,
+        auto,
+    })
+}
+
+// Runs `guessing_game code ...` and returns the exit code.
+pub fn run(args: &[String]) -> i32 {
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{USAGE}");
+        return 0;
+    }
+    let Options {
+        rules,
+        max_attempts,
+        seed,
+        auto,
+    } = match parse_args(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("Error: {e}\n{USAGE}");
+            return 2;
+        }
+    };
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let secret = rules.random(&mut rng);
+
+    let stdout = io::stdout();
+    let played = if auto {
+        solve(&rules, &secret, &mut stdout.lock()).map(|_| ())
+    } else {
+        play(
+            &rules,
+            &secret,
+            max_attempts,
+            &mut io::stdin().lock(),
+            &mut stdout.lock(),
+        )
+        .map(|_| ())
+    };
+    match played {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classic() -> Rules {
+        Rules::new(COLORS, 4, true).unwrap()
+    }
+
+    #[test]
+    fn bulls_and_cows() {
+        let rules = Rules::new(DIGITS, 4, false).unwrap();
+        let code = |text| rules.parse(text).unwrap();
+        assert_eq!(score(&code("1234"), &code("1234")), (4, 0));
+        assert_eq!(score(&code("1234"), &code("4321")), (0, 4));
+        assert_eq!(score(&code("1234"), &code("1567")), (1, 0));
+        assert_eq!(score(&code("1234"), &code("5612")), (0, 2));
+        // With duplicates every symbol counts once at most.
+        let rules = classic();
+        let code = |text| rules.parse(text).unwrap();
+        assert_eq!(score(&code("RRGB"), &code("RRRR")), (2, 0));
+        assert_eq!(score(&code("RGGB"), &code("GRYG")), (0, 3));
+        assert_eq!(score(&code("YYOP"), &code("OYYY")), (1, 2));
+    }
+
+    #[test]
+    fn rules_and_guesses() {
+        assert_eq!(classic().count(), 1296);
+        assert_eq!(classic().all_codes().len(), 1296);
+        assert_eq!(Rules::new(DIGITS, 4, false).unwrap().count(), 5040);
+        assert!(Rules::new(COLORS, 7, false).is_err());
+        assert!(Rules::new(DIGITS, 0, true).is_err());
+
+        let rules = Rules::new(DIGITS, 4, false).unwrap();
+        assert_eq!(rules.parse("0 4 1 7"), Ok(vec![0, 4, 1, 7]));
+        assert_eq!(
+            rules.parse("1231"),
+            Err("every symbol of the code is different, '1' appears twice".to_string())
+        );
+        assert_eq!(
+            rules.parse("123"),
+            Err("the code has 4 symbols, you typed 3".to_string())
+        );
+        assert!(rules.parse("12a4").is_err());
+        assert_eq!(classic().parse("rgby"), Ok(vec![0, 1, 2, 3]));
+
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..100 {
+            let code = rules.random(&mut rng);
+            assert_eq!(rules.parse(&rules.show(&code)), Ok(code));
+        }
+    }
+
+    // Knuth showed every classic Mastermind code falls in five guesses.
+    #[test]
+    fn solver_breaks_codes() {
+        let rules = classic();
+        for secret in rules.all_codes().into_iter().step_by(53) {
+            let guesses = solve(&rules, &secret, &mut io::sink()).unwrap();
+            assert!(guesses.is_some_and(|g| g <= 5), "{}", rules.show(&secret));
+        }
+        let rules = Rules::new(DIGITS, 3, false).unwrap();
+        for secret in rules.all_codes().into_iter().step_by(37) {
+            assert!(solve(&rules, &secret, &mut io::sink()).unwrap().is_some());
+        }
+        let huge = Rules::new(DIGITS, 6, true).unwrap();
+        assert!(Solver::check(&huge).is_err());
+    }
+
+    #[test]
+    fn solver_counts_the_codes_left_after_each_answer() {
+        let rules = Rules::new(DIGITS, 4, false).unwrap();
+        let mut output = Vec::new();
+        solve(&rules, &rules.parse("4567").unwrap(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        // None of 0123 is in the code, which leaves 6 * 5 * 4 * 3 codes.
+        assert!(output.starts_with("Guess 1: 0123 -> 0 bulls, 0 cows (360 codes left)\n"));
+        assert!(output.contains("-> 4 bulls, 0 cows (1 code left)\nBroke the code in "));
+    }
+
+    #[test]
+    fn options() {
+        let args =
+            |text: &str| -> Vec<String> { text.split_whitespace().map(String::from).collect() };
+        let options = parse_args(&args("--colors --length 5")).unwrap();
+        assert_eq!(options.rules, Rules::new(COLORS, 5, true).unwrap());
+        let options = parse_args(&args("--no-duplicates --auto --seed 3")).unwrap();
+        assert_eq!(options.rules, Rules::new(DIGITS, 4, false).unwrap());
+        assert_eq!(options.seed, Some(3));
+        let options = parse_args(&args("--seed 5000000000")).unwrap();
+        assert_eq!(options.seed, Some(5_000_000_000));
+        assert!(parse_args(&args("--colors --no-duplicates --length 7")).is_err());
+        assert!(parse_args(&args("--duplicates --length 5 --auto")).is_err());
+        assert!(parse_args(&args("--max-attempts 0")).is_err());
+        assert_eq!(
+            parse_args(&args("--auto --max-attempts 3"))
+                .err()
+                .as_deref(),
+            Some("--max-attempts cannot be used with --auto")
+        );
+        assert!(parse_args(&args("--length")).is_err());
+    }
+
+    #[test]
+    fn game() {
+        let rules = Rules::new(DIGITS, 4, false).unwrap();
+        let secret = rules.parse("1234").unwrap();
+        let mut output = Vec::new();
+        let result = play(
+            &rules,
+            &secret,
+            Some(3),
+            &mut "4321\n12\n1243\n1234\n".as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(result, (Outcome::Won, 3));
+        let output = String::from_utf8(output).unwrap();
+        assert!(output
+            .contains("0 bulls, 4 cows\nthe code has 4 symbols, you typed 2\n2 bulls, 2 cows\n"));
+
+        let mut output = Vec::new();
+        let result = play(
+            &rules,
+            &secret,
+            Some(1),
+            &mut "5678\n".as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(result, (Outcome::Lost, 1));
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .ends_with("Out of attempts, you lose! The code was 1234.\n"));
+    }
+}
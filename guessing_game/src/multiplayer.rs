@@ -0,0 +1,461 @@
+use std::cmp::Ordering;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use crate::config::{self, Config};
+use crate::game;
+
+pub const USAGE: &str = "Usage:
+  guessing_game serve [--bind <ADDR>] [--players <N>] [game options]
+      host a round for N players (2 by default) on ADDR (0.0.0.0:7878 by default)
+  guessing_game join <ADDR> [--name <NAME>]
+      join the round hosted at ADDR, e.g. join 192.168.1.20:7878
+
+The players take turns guessing the same number, everybody sees every guess
+and the first to find it wins.";
+
+// The line protocol, one message per line:
+//   client -> server: its name, then one guess whenever asked for it
+//   server -> client: text to show, TURN to ask for a guess, END when the
+//   round is over
+const TURN: &str = "TURN";
+const END: &str = "END";
+
+// How long a new connection has to send its name before the seat goes to
+// the next one, so a silent client cannot hold up the lobby.
+const NAME_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Player {
+    name: String,
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    attempts: u32,
+    // The player's own last guess, for --proximity answers.
+    previous: Option<u32>,
+}
+
+impl Player {
+    fn send(&mut self, line: &str) -> io::Result<()> {
+        writeln!(self.writer, "{line}")?;
+        self.writer.flush()
+    }
+
+    // The next line from the player, None once it has left.
+    fn receive(&mut self) -> Option<String> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line.trim().to_string()),
+        }
+    }
+}
+
+// Sends `line` to every player and to the host's `log`. Players that cannot
+// be reached any more are dropped.
+fn broadcast(players: &mut Vec<Player>, log: &mut impl Write, line: &str) -> io::Result<()> {
+    writeln!(log, "{line}")?;
+    players.retain_mut(|p| p.send(line).is_ok());
+    Ok(())
+}
+
+// Waits for `count` players on `listener` and plays one round against
+// `secret`. A connection that sends no name within `name_timeout` loses its
+// seat. Returns the winner's name, None if nobody found the number.
+pub fn serve(
+    listener: &TcpListener,
+    config: &Config,
+    secret: u32,
+    count: usize,
+    name_timeout: Duration,
+    log: &mut impl Write,
+) -> io::Result<Option<String>> {
+    let mut players: Vec<Player> = Vec::new();
+    while players.len() < count {
+        let (stream, address) = listener.accept()?;
+        let mut player = Player {
+            name: String::new(),
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+            attempts: 0,
+            previous: None,
+        };
+        player.writer.set_read_timeout(Some(name_timeout))?;
+        let Some(name) = player.receive().filter(|n| !n.is_empty()) else {
+            continue;
+        };
+        // Once seated the player may think as long as they like.
+        player.writer.set_read_timeout(None)?;
+        // Two players with one name could not tell whose turn it is.
+        let taken = |name: &str| players.iter().any(|p| p.name == name);
+        player.name = name.clone();
+        let mut n = 2;
+        while taken(&player.name) {
+            player.name = format!("{name} ({n})");
+            n += 1;
+        }
+        let hello = match count - players.len() - 1 {
+            1 => format!("Hello {}, waiting for 1 more player.", player.name),
+            more => format!("Hello {}, waiting for {more} more players.", player.name),
+        };
+        // Someone who hangs up right away does not take a seat.
+        if player.send(&hello).is_ok() {
+            writeln!(log, "{} joined from {address}", player.name)?;
+            players.push(player);
+        }
+    }
+
+    let names: Vec<String> = players.iter().map(|p| p.name.clone()).collect();
+    broadcast(
+        &mut players,
+        log,
+        &format!(
+            "Players: {}. Pick a number from {} to {}!",
+            names.join(", "),
+            config.min,
+            config.max
+        ),
+    )?;
+    if let Some(max_attempts) = config.max_attempts {
+        broadcast(
+            &mut players,
+            log,
+            &format!("Everybody has {max_attempts} attempts."),
+        )?;
+    }
+
+    let mut guessed: Vec<u32> = Vec::new();
+    let mut turn = 0;
+    let winner = loop {
+        // Players that are out of attempts sit out the rest of the round.
+        let waiting = |p: &Player| config.max_attempts.is_none_or(|max| p.attempts < max);
+        if !players.iter().any(waiting) {
+            break None;
+        }
+        turn %= players.len();
+        if !waiting(&players[turn]) {
+            turn += 1;
+            continue;
+        }
+
+        let player = &mut players[turn];
+        let guess = loop {
+            if player.send(TURN).is_err() {
+                break None;
+            }
+            let Some(text) = player.receive() else {
+                break None;
+            };
+            match game::parse_guess(&text, config) {
+                Ok(guess) if guessed.contains(&guess) => {
+                    let _ =
+                        player.send(&format!("{guess} was already guessed, try another number."));
+                }
+                Ok(guess) => break Some(guess),
+                Err(message) => {
+                    let _ = player.send(&message);
+                }
+            }
+        };
+        let Some(guess) = guess else {
+            let left = players.remove(turn);
+            broadcast(&mut players, log, &format!("{} left the game.", left.name))?;
+            if players.is_empty() {
+                break None;
+            }
+            continue;
+        };
+
+        player.attempts += 1;
+        guessed.push(guess);
+        let name = player.name.clone();
+        let previous = player.previous.replace(guess);
+        let answer = match guess.cmp(&secret) {
+            _ if guess != secret && config.proximity => {
+                game::proximity(config, secret, guess, previous)
+            }
+            Ordering::Less => "too low.".to_string(),
+            Ordering::Greater => "too big.".to_string(),
+            Ordering::Equal => {
+                broadcast(
+                    &mut players,
+                    log,
+                    &format!("{name} guessed {guess}, that's it! {name} wins!"),
+                )?;
+                break Some(name);
+            }
+        };
+        broadcast(
+            &mut players,
+            log,
+            &format!("{name} guessed {guess}: {answer}"),
+        )?;
+        turn += 1;
+    };
+
+    if winner.is_none() {
+        broadcast(
+            &mut players,
+            log,
+            &format!("Nobody found it, the number was {secret}."),
+        )?;
+    }
+    // END is for the clients only, the host has seen the result.
+    for player in &mut players {
+        let _ = player.send(END);
+    }
+    Ok(winner)
+}
+
+// Plays as `name` on the server behind `stream`, reading guesses from
+// `input` whenever it is this player's turn.
+pub fn join(
+    stream: TcpStream,
+    name: &str,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    writeln!(writer, "{name}")?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        match line.as_str() {
+            END => return Ok(()),
+            TURN => {
+                write!(output, "Your guess: ")?;
+                output.flush()?;
+                let mut guess = String::new();
+                if input.read_line(&mut guess)? == 0 {
+                    // Leaving the round closes the connection.
+                    return Ok(());
+                }
+                writeln!(writer, "{}", guess.trim())?;
+            }
+            _ => writeln!(output, "{line}")?,
+        }
+    }
+    writeln!(output, "The server closed the connection.")?;
+    Ok(())
+}
+
+// Runs `guessing_game serve ...` and returns the exit code.
+pub fn run_server(args: &[String]) -> i32 {
+    let mut bind = "0.0.0.0:7878".to_string();
+    let mut count = 2;
+    let mut rest = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return 0;
+            }
+            "--bind" => match args.next() {
+                Some(address) => bind = address.clone(),
+                None => {
+                    eprintln!("Error: --bind needs an address\n{USAGE}");
+                    return 2;
+                }
+            },
+            "--players" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) if n > 0 => count = n,
+                _ => {
+                    eprintln!("Error: --players needs a number above 0\n{USAGE}");
+                    return 2;
+                }
+            },
+            _ => rest.push(arg.clone()),
+        }
+    }
+    let config = match config::parse_args(&rest) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {e}\n{}", config::USAGE);
+            return 2;
+        }
+    };
+    if config.time_limit.is_some() {
+        eprintln!("Error: --time-limit is not available over the network\n{USAGE}");
+        return 2;
+    }
+
+    let listener = match TcpListener::bind(&bind) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Error: cannot listen on {bind}: {e}");
+            return 1;
+        }
+    };
+    let address = listener.local_addr().map_or(bind, |a| a.to_string());
+    println!("Waiting for {count} players on {address}");
+    match serve(
+        &listener,
+        &config,
+        config.pick_secret(),
+        count,
+        NAME_TIMEOUT,
+        &mut io::stdout(),
+    ) {
+        Ok(_) => 0,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    }
+}
+
+// Runs `guessing_game join <ADDR>` and returns the exit code.
+pub fn run_client(args: &[String], name: &str) -> i32 {
+    let address = match args {
+        [flag, ..] if flag == "--help" || flag == "-h" => {
+            println!("{USAGE}");
+            return 0;
+        }
+        [address] => address,
+        _ => {
+            eprintln!("Error: join needs the address of the server\n{USAGE}");
+            return 2;
+        }
+    };
+    let stream = match TcpStream::connect(address) {
+        Ok(stream) => stream,
+        Err(e) => {
+            eprintln!("Error: cannot connect to {address}: {e}");
+            return 1;
+        }
+    };
+    match join(stream, name, &mut io::stdin().lock(), &mut io::stdout()) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    // Runs a round with one client per `(name, guesses)` on localhost,
+    // returning the winner, the host's log and what each client saw.
+    fn round(
+        config: Config,
+        secret: u32,
+        clients: &[(&str, &str)],
+    ) -> (Option<String>, String, Vec<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let count = clients.len();
+        let server = thread::spawn(move || {
+            let mut log = Vec::new();
+            let winner = serve(&listener, &config, secret, count, NAME_TIMEOUT, &mut log).unwrap();
+            (winner, String::from_utf8(log).unwrap())
+        });
+
+        // Connecting one after the other fixes the order of the turns.
+        let outputs: Vec<_> = clients
+            .iter()
+            .map(|(name, guesses)| {
+                let stream = TcpStream::connect(address).unwrap();
+                let (name, guesses) = (name.to_string(), guesses.to_string());
+                thread::spawn(move || {
+                    let mut output = Vec::new();
+                    join(stream, &name, &mut guesses.as_bytes(), &mut output).unwrap();
+                    String::from_utf8(output).unwrap()
+                })
+            })
+            .collect();
+        let outputs = outputs.into_iter().map(|t| t.join().unwrap()).collect();
+        let (winner, log) = server.join().unwrap();
+        (winner, log, outputs)
+    }
+
+    #[test]
+    fn first_to_find_it_wins() {
+        let (winner, log, outputs) = round(
+            Config::default(),
+            42,
+            &[("ann", "50\n30\n"), ("bob", "ten\n50\n10\n42\n")],
+        );
+        assert_eq!(winner.as_deref(), Some("bob"));
+        assert!(log.contains(
+            "ann guessed 50: too big.\nbob guessed 10: too low.\nann guessed 30: too low.\nbob guessed 42, that's it! bob wins!"
+        ));
+        assert!(outputs[0].starts_with("Hello ann, waiting for 1 more player.\nPlayers: ann, bob."));
+        assert!(outputs[0].contains("bob wins!"));
+        // Only bob is told about his own mistakes.
+        assert!(outputs[1].contains("'ten' is not a number"));
+        assert!(outputs[1].contains("50 was already guessed"));
+        assert!(!outputs[0].contains("already guessed"));
+    }
+
+    #[test]
+    fn nobody_wins_or_everybody_leaves() {
+        let config = Config {
+            max_attempts: Some(1),
+            ..Config::default()
+        };
+        let (winner, log, outputs) = round(config, 42, &[("ann", "1\n"), ("ann", "2\n")]);
+        assert_eq!(winner, None);
+        assert!(log.contains("ann (2) guessed 2: too low."));
+        assert!(outputs[1].ends_with("Nobody found it, the number was 42.\n"));
+
+        let (winner, log, _) = round(Config::default(), 42, &[("ann", "1\n"), ("bob", "")]);
+        assert_eq!(winner, None);
+        assert!(log.contains("bob left the game.\n"));
+        assert!(log.contains("ann left the game.\n"));
+    }
+
+    #[test]
+    fn proximity_answers_each_players_own_guesses() {
+        let config = Config {
+            proximity: true,
+            ..Config::default()
+        };
+        let (winner, log, _) = round(config, 42, &[("ann", "90\n50\n"), ("bob", "10\n42\n")]);
+        assert_eq!(winner.as_deref(), Some("bob"));
+        assert!(log.contains(
+            "ann guessed 90: Cold!\nbob guessed 10: Cold!\nann guessed 50: Warm, warmer than 90.\nbob guessed 42, that's it!"
+        ));
+    }
+
+    #[test]
+    fn renamed_players_get_unique_names() {
+        let (_, log, outputs) = round(
+            Config::default(),
+            42,
+            &[("ann (2)", "1\n"), ("ann", "2\n"), ("ann", "42\n")],
+        );
+        assert!(log
+            .contains("ann (2) guessed 1: too low.\nann guessed 2: too low.\nann (3) guessed 42"));
+        assert!(outputs[0].starts_with("Hello ann (2), waiting for 2 more players."));
+    }
+
+    #[test]
+    fn silent_connections_do_not_hold_up_the_lobby() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        // Connects first but never says its name.
+        let silent = TcpStream::connect(address).unwrap();
+        let server = thread::spawn(move || {
+            let timeout = Duration::from_millis(300);
+            serve(
+                &listener,
+                &Config::default(),
+                42,
+                1,
+                timeout,
+                &mut io::sink(),
+            )
+            .unwrap()
+        });
+        let stream = TcpStream::connect(address).unwrap();
+        let mut output = Vec::new();
+        join(stream, "ann", &mut "42\n".as_bytes(), &mut output).unwrap();
+        assert_eq!(server.join().unwrap().as_deref(), Some("ann"));
+        assert!(String::from_utf8(output).unwrap().contains("ann wins!"));
+        drop(silent);
+    }
+}
@@ -0,0 +1,194 @@
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+
+use crate::config::Config;
+use crate::solver::Bisection;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Ending {
+    Found { number: u32, guesses: u32 },
+    // The answers ruled out every number, with the explanation shown.
+    Contradiction(String),
+    // --max-attempts ran out before the number was found.
+    OutOfGuesses,
+    InputEnded,
+}
+
+// The player's answer to a guess, as `guess.cmp(&number)`.
+fn answer(text: &str) -> Option<Ordering> {
+    match text.trim().to_lowercase().as_str() {
+        "higher" | "h" | "more" | "bigger" | ">" => Some(Ordering::Less),
+        "lower" | "l" | "less" | "smaller" | "<" => Some(Ordering::Greater),
+        "correct" | "c" | "yes" | "y" | "=" => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+// Why no number is left: the answers that set each end of the range, or the
+// end of the range itself when no answer moved it.
+fn contradiction(config: &Config, higher_than: Option<u32>, lower_than: Option<u32>) -> String {
+    match (higher_than, lower_than) {
+        (Some(low), Some(high)) => {
+            format!("you said higher than {low} and lower than {high}, no number fits both")
+        }
+        (Some(low), None) => format!(
+            "you said higher than {low}, but the number is at most {}",
+            config.max
+        ),
+        (None, Some(high)) => format!(
+            "you said lower than {high}, but the number is at least {}",
+            config.min
+        ),
+        (None, None) => "no number fits your answers".to_string(),
+    }
+}
+
+// `--reverse`: the player thinks of a number and the computer guesses it by
+// bisection, answered with higher, lower or correct.
+pub fn play(
+    config: &Config,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<Ending> {
+    writeln!(
+        output,
+        "Think of a number from {} to {} and I will guess it.\nAnswer each guess with higher, lower or correct.",
+        config.min, config.max
+    )?;
+
+    let mut bisection = Bisection::new(config.min, config.max);
+    // The guesses that the latest "higher" and "lower" answers were about.
+    let (mut higher_than, mut lower_than) = (None, None);
+    let mut guesses = 0;
+    while let Some(guess) = bisection.next_guess() {
+        if config.max_attempts == Some(guesses) {
+            writeln!(output, "I'm out of guesses, you win! What was your number?")?;
+            return Ok(Ending::OutOfGuesses);
+        }
+        guesses += 1;
+        writeln!(output, "My guess is {guess}.")?;
+        output.flush()?;
+
+        let ordering = loop {
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                return Ok(Ending::InputEnded);
+            }
+            match answer(&line) {
+                Some(ordering) => break ordering,
+                None => writeln!(output, "Please answer higher, lower or correct.")?,
+            }
+            output.flush()?;
+        };
+
+        match ordering {
+            Ordering::Equal => {
+                writeln!(
+                    output,
+                    "Got it, your number is {guess}! It took me {guesses} guesses."
+                )?;
+                return Ok(Ending::Found {
+                    number: guess,
+                    guesses,
+                });
+            }
+            Ordering::Less => higher_than = Some(guess),
+            Ordering::Greater => lower_than = Some(guess),
+        }
+        bisection.answer(guess, ordering);
+    }
+
+    let reason = contradiction(config, higher_than, lower_than);
+    writeln!(output, "That can't be right: {reason}.")?;
+    Ok(Ending::Contradiction(reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(config: &Config, input: &str) -> (Ending, String) {
+        let mut output = Vec::new();
+        let ending = play(config, &mut input.as_bytes(), &mut output).unwrap();
+        (ending, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn finds_the_number() {
+        // Thinking of 42: 50 lower, 25 higher, 37 higher, 43 lower, 40 higher, 41 higher.
+        let (ending, output) = run(
+            &Config::default(),
+            "lower\nh\nmaybe\nhigher\n<\n>\nhigher\ncorrect\n",
+        );
+        assert_eq!(
+            ending,
+            Ending::Found {
+                number: 42,
+                guesses: 7
+            }
+        );
+        assert_eq!(output.matches("Please answer").count(), 1);
+        assert!(output.ends_with("Got it, your number is 42! It took me 7 guesses.\n"));
+    }
+
+    #[test]
+    fn detects_contradictions() {
+        // 50 higher, 75 lower, 62 lower, 56 lower, 53 lower, 51 lower: nothing left.
+        let (ending, output) = run(&Config::default(), "higher\nlower\nl\nl\nl\nl\n");
+        assert_eq!(
+            ending,
+            Ending::Contradiction(
+                "you said higher than 50 and lower than 51, no number fits both".to_string()
+            )
+        );
+        assert!(output.ends_with("no number fits both.\n"));
+
+        let (ending, _) = run(&Config::default(), &"higher\n".repeat(7));
+        assert_eq!(
+            ending,
+            Ending::Contradiction(
+                "you said higher than 100, but the number is at most 100".to_string()
+            )
+        );
+        let config = Config {
+            min: 0,
+            max: 3,
+            ..Config::default()
+        };
+        let (ending, _) = run(&config, "lower\nlower\n");
+        assert_eq!(
+            ending,
+            Ending::Contradiction(
+                "you said lower than 0, but the number is at least 0".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn contradiction_at_the_top_of_u32() {
+        let config = Config {
+            min: u32::MAX - 5,
+            max: u32::MAX,
+            ..Config::default()
+        };
+        // 4294967292, 4294967294 and 4294967295 all answered higher.
+        let (ending, output) = run(&config, &"higher\n".repeat(4));
+        assert_eq!(
+            ending,
+            Ending::Contradiction(
+                "you said higher than 4294967295, but the number is at most 4294967295".to_string()
+            )
+        );
+        assert_eq!(output.matches("My guess is").count(), 3);
+    }
+
+    #[test]
+    fn limited_guesses_and_closed_input() {
+        let config = Config {
+            max_attempts: Some(2),
+            ..Config::default()
+        };
+        assert_eq!(run(&config, "higher\nhigher\n").0, Ending::OutOfGuesses);
+        assert_eq!(run(&Config::default(), "higher\n").0, Ending::InputEnded);
+    }
+}
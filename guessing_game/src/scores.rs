@@ -0,0 +1,511 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::game::{Outcome, Summary};
+
+pub const USAGE: &str = "Usage:
+  guessing_game scores [--top <N>]   leaderboards and the statistics of --name
+
+Every won or lost game without --seed is recorded in the file of --scores,
+which defaults to $GUESSING_GAME_SCORES or ~/.guessing_game_scores.json.
+The leaderboards rank the --difficulty presets, with --proximity games and
+each --time-limit on boards of their own, timed games by their score. Games
+on a range of your own or with hints only count in your statistics.";
+
+const DIFFICULTIES: [&str; 3] = ["easy", "medium", "hard"];
+
+fn classic() -> String {
+    "classic".to_string()
+}
+
+// One finished game, as stored in the JSON file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub name: String,
+    // "easy", "medium", "hard" or "custom".
+    pub difficulty: String,
+    pub min: u32,
+    pub max: u32,
+    pub won: bool,
+    pub attempts: u32,
+    pub seconds: f64,
+    // Day the game was played, YYYY-MM-DD in UTC.
+    pub date: String,
+    // Hints asked for, missing in files written before hints were counted.
+    #[serde(default)]
+    pub hints: u32,
+    // "classic" or "proximity", the answers a game gave. Older files only
+    // hold classic games.
+    #[serde(default = "classic")]
+    pub mode: String,
+    // Seconds of a --time-limit challenge, and the points a win scored.
+    #[serde(default)]
+    pub time_limit: Option<u32>,
+    #[serde(default)]
+    pub score: Option<u32>,
+}
+
+impl Record {
+    // None for a game that was not finished, which is not worth keeping.
+    pub fn new(name: &str, config: &Config, summary: &Summary, seconds: f64) -> Option<Record> {
+        let won = match summary.outcome {
+            Outcome::Won => true,
+            Outcome::Lost => false,
+            Outcome::Abandoned | Outcome::Quit => return None,
+        };
+        Some(Record {
+            name: name.to_string(),
+            difficulty: config.difficulty.map_or("custom", |d| d.name()).to_string(),
+            min: config.min,
+            max: config.max,
+            won,
+            attempts: summary.attempts,
+            seconds,
+            date: today(),
+            hints: summary.hints,
+            mode: if config.proximity {
+                "proximity"
+            } else {
+                "classic"
+            }
+            .to_string(),
+            time_limit: config.time_limit,
+            score: summary.score,
+        })
+    }
+
+    // Leaderboard the game is ranked on, like "hard", "hard, proximity" or
+    // "hard, timed 60 s", as a longer time limit leaves more points to win.
+    // A custom range has none, one guess out of 1 to 2 is not worth
+    // comparing with the presets, and neither has a game played with hints,
+    // which bisect for the player.
+    pub fn board(&self) -> Option<String> {
+        if self.difficulty == "custom" || self.hints > 0 {
+            return None;
+        }
+        let mut board = self.difficulty.clone();
+        if self.mode != "classic" {
+            board += &format!(", {}", self.mode);
+        }
+        if let Some(limit) = self.time_limit {
+            board += &format!(", timed {limit} s");
+        }
+        Some(board)
+    }
+}
+
+// Civil date of a Unix timestamp, using Howard Hinnant's days_from_civil
+// algorithm backwards: http://howardhinnant.github.io/date_algorithms.html
+fn date(timestamp: u64) -> String {
+    let z = (timestamp / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn today() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    date(now)
+}
+
+// --scores, else $GUESSING_GAME_SCORES, else a file in the home directory.
+pub fn path(explicit: Option<&str>) -> PathBuf {
+    if let Some(path) = explicit
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("GUESSING_GAME_SCORES").map(PathBuf::from))
+    {
+        return path;
+    }
+    let home = env::var_os("HOME").map_or_else(PathBuf::new, PathBuf::from);
+    home.join(".guessing_game_scores.json")
+}
+
+// The player's name: --name, else the login name.
+pub fn player(explicit: Option<String>) -> String {
+    explicit
+        .or_else(|| env::var("USER").ok())
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| "player".to_string())
+}
+
+pub fn load(path: &PathBuf) -> Result<Vec<Record>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text =
+        fs::read_to_string(path).map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+    serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+pub fn add(path: &PathBuf, record: Record) -> Result<(), String> {
+    let mut records = load(path)?;
+    records.push(record);
+    let text = serde_json::to_string_pretty(&records).map_err(|e| e.to_string())?;
+    fs::write(path, text + "\n").map_err(|e| format!("cannot write {}: {e}", path.display()))
+}
+
+// Won games of one board, the highest score first on timed boards, else
+// fewest attempts first, then the fastest.
+pub fn leaderboard<'a>(records: &'a [Record], board: &str) -> Vec<&'a Record> {
+    let mut wins: Vec<&Record> = records
+        .iter()
+        .filter(|r| r.won && r.board().as_deref() == Some(board))
+        .collect();
+    wins.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(a.attempts.cmp(&b.attempts))
+            .then(a.seconds.total_cmp(&b.seconds))
+    });
+    wins
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Stats {
+    pub games: u32,
+    pub wins: u32,
+    // Averages over won games only.
+    pub average_attempts: f64,
+    pub average_seconds: f64,
+    // Wins in a row up to the last game, and the longest such run.
+    pub streak: u32,
+    pub best_streak: u32,
+}
+
+pub fn stats(records: &[Record], name: &str) -> Stats {
+    let mut stats = Stats::default();
+    for record in records.iter().filter(|r| r.name == name) {
+        stats.games += 1;
+        if record.won {
+            stats.wins += 1;
+            stats.average_attempts += f64::from(record.attempts);
+            stats.average_seconds += record.seconds;
+            stats.streak += 1;
+            stats.best_streak = stats.best_streak.max(stats.streak);
+        } else {
+            stats.streak = 0;
+        }
+    }
+    if stats.wins > 0 {
+        stats.average_attempts /= f64::from(stats.wins);
+        stats.average_seconds /= f64::from(stats.wins);
+    }
+    stats
+}
+
+pub fn report(records: &[Record], name: &str, top: usize) -> String {
+    let mut lines = Vec::new();
+    // Boards of the easy games first, then medium and hard.
+    let mut boards: Vec<(usize, String)> = records
+        .iter()
+        .filter(|r| r.won)
+        .filter_map(|r| {
+            let order = DIFFICULTIES.iter().position(|d| *d == r.difficulty)?;
+            Some((order, r.board()?))
+        })
+        .collect();
+    boards.sort();
+    boards.dedup();
+    for (_, board) in boards {
+        let wins = leaderboard(records, &board);
+        lines.push(format!("Leaderboard, {board}:"));
+        for (place, r) in wins.iter().take(top).enumerate() {
+            let points = r
+                .score
+                .map_or(String::new(), |score| format!("{score:>5} points "));
+            lines.push(format!(
+                "  {:>2}. {:<12} {points}{:>3} attempts {:>7.1} s   {} to {}, {}",
+                place + 1,
+                r.name,
+                r.attempts,
+                r.seconds,
+                r.min,
+                r.max,
+                r.date
+            ));
+        }
+    }
+    if lines.is_empty() {
+        lines.push("No games recorded yet.".to_string());
+    }
+
+    let stats = stats(records, name);
+    lines.push(String::new());
+    if stats.games == 0 {
+        lines.push(format!("{name} has not played yet."));
+    } else {
+        lines.push(format!(
+            "{name}: {} games, {} won ({:.0}%)",
+            stats.games,
+            stats.wins,
+            100.0 * f64::from(stats.wins) / f64::from(stats.games)
+        ));
+        if stats.wins > 0 {
+            lines.push(format!(
+                "  {:.1} attempts and {:.1} s per win on average",
+                stats.average_attempts, stats.average_seconds
+            ));
+        }
+        lines.push(format!(
+            "  winning streak {}, best {}",
+            stats.streak, stats.best_streak
+        ));
+    }
+    lines.join("\n")
+}
+
+// Runs `guessing_game scores ...` and returns the exit code.
+pub fn run(args: &[String], path: &PathBuf, name: &str) -> i32 {
+    let mut top = 10;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return 0;
+            }
+            "--top" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) => top = n,
+                None => {
+                    eprintln!("Error: --top needs a number\n{USAGE}");
+                    return 2;
+                }
+            },
+            _ => {
+                eprintln!("Error: unexpected argument '{arg}'\n{USAGE}");
+                return 2;
+            }
+        }
+    }
+    match load(path) {
+        Ok(records) => {
+            println!("{}", report(&records, name, top));
+            0
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(name: &str, difficulty: &str, won: bool, attempts: u32, seconds: f64) -> Record {
+        Record {
+            name: name.to_string(),
+            difficulty: difficulty.to_string(),
+            min: 1,
+            max: 100,
+            won,
+            attempts,
+            seconds,
+            date: "2024-05-01".to_string(),
+            hints: 0,
+            mode: classic(),
+            time_limit: None,
+            score: None,
+        }
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(date(951_782_400), "2000-02-29");
+        assert_eq!(date(1_700_000_000), "2023-11-14");
+    }
+
+    #[test]
+    fn leaderboard_order() {
+        let records = [
+            record("ann", "medium", true, 6, 20.0),
+            record("bob", "medium", true, 4, 30.0),
+            record("cid", "medium", true, 4, 12.5),
+            record("dan", "medium", false, 7, 5.0),
+            record("eve", "hard", true, 1, 1.0),
+        ];
+        let names: Vec<&str> = leaderboard(&records, "medium")
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(names, ["cid", "bob", "ann"]);
+    }
+
+    #[test]
+    fn proximity_games_have_their_own_boards() {
+        let warm = Record {
+            mode: "proximity".to_string(),
+            ..record("ann", "medium", true, 3, 10.0)
+        };
+        let records = [
+            warm,
+            record("bob", "medium", true, 5, 20.0),
+            record("cid", "easy", true, 4, 8.0),
+        ];
+        let names = |board| -> Vec<&str> {
+            leaderboard(&records, board)
+                .iter()
+                .map(|r| r.name.as_str())
+                .collect()
+        };
+        assert_eq!(names("medium"), ["bob"]);
+        assert_eq!(names("medium, proximity"), ["ann"]);
+        let report = report(&records, "ann", 10);
+        let titles: Vec<&str> = report
+            .lines()
+            .filter(|l| l.starts_with("Leaderboard"))
+            .collect();
+        assert_eq!(
+            titles,
+            [
+                "Leaderboard, easy:",
+                "Leaderboard, medium:",
+                "Leaderboard, medium, proximity:"
+            ]
+        );
+        let config = Config {
+            proximity: true,
+            ..crate::config::Difficulty::Hard.config()
+        };
+        let summary = Summary {
+            outcome: Outcome::Won,
+            attempts: 6,
+            secret: 42,
+            hints: 0,
+            score: None,
+        };
+        let record = Record::new("ann", &config, &summary, 12.0).unwrap();
+        assert_eq!(record.board().as_deref(), Some("hard, proximity"));
+    }
+
+    #[test]
+    fn timed_games_rank_by_score() {
+        let timed = |name, attempts, seconds, limit, score| Record {
+            time_limit: Some(limit),
+            score: Some(score),
+            ..record(name, "medium", true, attempts, seconds)
+        };
+        let records = [
+            timed("ann", 5, 20.0, 60, 600),
+            timed("bob", 4, 50.0, 60, 350),
+            timed("cid", 6, 10.0, 60, 666),
+            timed("dan", 1, 1.0, 120, 2190),
+            record("eve", "medium", true, 7, 5.0),
+        ];
+        let names = |board| -> Vec<&str> {
+            leaderboard(&records, board)
+                .iter()
+                .map(|r| r.name.as_str())
+                .collect()
+        };
+        assert_eq!(names("medium, timed 60 s"), ["cid", "ann", "bob"]);
+        assert_eq!(names("medium, timed 120 s"), ["dan"]);
+        assert_eq!(names("medium"), ["eve"]);
+        let report = report(&records, "ann", 10);
+        assert!(report.contains(
+            "Leaderboard, medium, timed 60 s:\n   1. cid            666 points   6 attempts    10.0 s"
+        ), "{report}");
+
+        let config = Config {
+            time_limit: Some(30),
+            ..crate::config::Difficulty::Easy.config()
+        };
+        let summary = Summary {
+            outcome: Outcome::Won,
+            attempts: 2,
+            secret: 7,
+            hints: 0,
+            score: Some(700),
+        };
+        let record = Record::new("ann", &config, &summary, 4.0).unwrap();
+        assert_eq!(record.score, Some(700));
+        assert_eq!(record.board().as_deref(), Some("easy, timed 30 s"));
+    }
+
+    #[test]
+    fn hinted_games_only_count_in_stats() {
+        let hinted = Record {
+            hints: 2,
+            ..record("ann", "hard", true, 10, 30.0)
+        };
+        let records = [hinted, record("bob", "hard", true, 12, 40.0)];
+        let names: Vec<&str> = leaderboard(&records, "hard")
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(names, ["bob"]);
+        assert_eq!(stats(&records, "ann").wins, 1);
+        let summary = Summary {
+            outcome: Outcome::Won,
+            attempts: 6,
+            secret: 42,
+            hints: 1,
+            score: None,
+        };
+        let config = crate::config::Difficulty::Medium.config();
+        let record = Record::new("ann", &config, &summary, 12.0).unwrap();
+        assert_eq!(record.board(), None);
+        // Files from before hints were counted still load.
+        let old = r#"[{"name":"ann","difficulty":"easy","min":1,"max":50,"won":true,"attempts":5,"seconds":9.5,"date":"2024-05-01"}]"#;
+        let records: Vec<Record> = serde_json::from_str(old).unwrap();
+        assert_eq!(records[0].hints, 0);
+        assert_eq!(records[0].board().as_deref(), Some("easy"));
+    }
+
+    #[test]
+    fn custom_ranges_only_count_in_stats() {
+        let records = [
+            record("ann", "custom", true, 1, 1.0),
+            record("bob", "hard", true, 9, 40.0),
+        ];
+        assert!(leaderboard(&records, "custom").is_empty());
+        let report = report(&records, "ann", 10);
+        assert!(
+            report.starts_with("Leaderboard, hard:\n   1. bob"),
+            "{report}"
+        );
+        assert!(!report.contains("custom"));
+        assert!(!report.contains("1. ann"));
+        assert!(report.contains("ann: 1 games, 1 won (100%)"));
+    }
+
+    #[test]
+    fn averages_and_streaks() {
+        let records = [
+            record("ann", "easy", true, 4, 10.0),
+            record("ann", "easy", true, 6, 20.0),
+            record("bob", "easy", false, 10, 60.0),
+            record("ann", "hard", true, 8, 30.0),
+            record("ann", "hard", false, 10, 50.0),
+            record("ann", "easy", true, 2, 5.0),
+        ];
+        assert_eq!(
+            stats(&records, "ann"),
+            Stats {
+                games: 5,
+                wins: 4,
+                average_attempts: 5.0,
+                average_seconds: 16.25,
+                streak: 1,
+                best_streak: 3,
+            }
+        );
+        assert_eq!(stats(&records, "zoe"), Stats::default());
+    }
+}
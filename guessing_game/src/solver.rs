@@ -0,0 +1,213 @@
+use std::cmp::Ordering;
+use std::io::{self, Write};
+
+use crate::config::Config;
+use crate::game::{Outcome, Summary};
+
+// Guesses a perfect bisection needs in the worst case to find one of `n`
+// numbers: ceil(log2(n + 1)), which is the number of bits of `n`.
+pub fn worst_case(n: u64) -> u32 {
+    u64::BITS - n.leading_zeros()
+}
+
+// What the answers so far say about the secret: it is in low..=high.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bisection {
+    pub low: u32,
+    pub high: u32,
+}
+
+impl Bisection {
+    pub fn new(min: u32, max: u32) -> Bisection {
+        Bisection {
+            low: min,
+            high: max,
+        }
+    }
+
+    // Numbers the secret can still be, 0 if the answers contradict each other.
+    pub fn remaining(&self) -> u64 {
+        if self.low > self.high {
+            0
+        } else {
+            u64::from(self.high - self.low) + 1
+        }
+    }
+
+    // The middle of what is left, None once nothing is.
+    pub fn next_guess(&self) -> Option<u32> {
+        (self.remaining() > 0).then(|| self.low + (self.high - self.low) / 2)
+    }
+
+    // Narrows the range by the answer to `guess`, `guess.cmp(&secret)`.
+    pub fn answer(&mut self, guess: u32, ordering: Ordering) {
+        match ordering {
+            Ordering::Less => match guess.checked_add(1) {
+                Some(above) => self.low = self.low.max(above),
+                // Nothing is above u32::MAX, so no number is left.
+                None => (self.low, self.high) = (1, 0),
+            },
+            Ordering::Greater => match guess.checked_sub(1) {
+                Some(below) => self.high = self.high.min(below),
+                // Nothing is below 0, so no number is left.
+                None => (self.low, self.high) = (1, 0),
+            },
+            Ordering::Equal => (self.low, self.high) = (guess, guess),
+        }
+    }
+
+    // A line of advice for the player.
+    pub fn hint(&self) -> String {
+        match self.next_guess() {
+            Some(guess) if self.remaining() == 1 => {
+                format!("Hint: it can only be {guess}.")
+            }
+            Some(guess) => format!(
+                "Hint: the number is between {} and {}. Try {guess}, {} more guesses at most will find it.",
+                self.low,
+                self.high,
+                worst_case(self.remaining())
+            ),
+            None => "Hint: no number fits all the answers so far.".to_string(),
+        }
+    }
+}
+
+// `--auto`: the computer plays against itself by bisection and reports
+// every guess, so it can be checked against the worst case.
+pub fn play(config: &Config, secret: u32, output: &mut impl Write) -> io::Result<Summary> {
+    let mut bisection = Bisection::new(config.min, config.max);
+    let worst = worst_case(bisection.remaining());
+    writeln!(
+        output,
+        "The computer looks for a number from {} to {}, which takes at most {worst} guesses.",
+        config.min, config.max
+    )?;
+
+    let mut summary = Summary {
+        outcome: Outcome::Abandoned,
+        attempts: 0,
+        secret,
+        hints: 0,
+        score: None,
+    };
+    while let Some(guess) = bisection.next_guess() {
+        summary.attempts += 1;
+        let ordering = guess.cmp(&secret);
+        let answer = match ordering {
+            Ordering::Less => "too low",
+            Ordering::Greater => "too big",
+            Ordering::Equal => "correct",
+        };
+        writeln!(output, "Guess {}: {guess} is {answer}", summary.attempts)?;
+        if ordering == Ordering::Equal {
+            writeln!(
+                output,
+                "Found {secret} in {} guesses (worst case {worst}).",
+                summary.attempts
+            )?;
+            summary.outcome = Outcome::Won;
+            break;
+        }
+        if config.max_attempts == Some(summary.attempts) {
+            writeln!(output, "Out of attempts, the number was {secret}.")?;
+            summary.outcome = Outcome::Lost;
+            break;
+        }
+        bisection.answer(guess, ordering);
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn worst_cases() {
+        assert_eq!(worst_case(1), 1);
+        assert_eq!(worst_case(2), 2);
+        assert_eq!(worst_case(3), 2);
+        assert_eq!(worst_case(100), 7);
+        assert_eq!(worst_case(1000), 10);
+        assert_eq!(worst_case(u64::from(u32::MAX) + 1), 33);
+    }
+
+    // Every secret of every range up to 300 numbers is found within the
+    // worst case, and some secret needs all of it.
+    #[test]
+    fn always_solvable_within_the_worst_case() {
+        for size in 1..=300u32 {
+            let config = Config {
+                min: 10,
+                max: 10 + size - 1,
+                ..Config::default()
+            };
+            let worst = worst_case(u64::from(size));
+            let mut hardest = 0;
+            for secret in config.min..=config.max {
+                let summary = play(&config, secret, &mut io::sink()).unwrap();
+                assert_eq!(summary.outcome, Outcome::Won);
+                assert!(summary.attempts <= worst, "{secret} of {size}");
+                hardest = hardest.max(summary.attempts);
+            }
+            assert_eq!(hardest, worst, "range of {size}");
+        }
+    }
+
+    #[test]
+    fn edges_of_u32() {
+        let config = Config {
+            min: 0,
+            max: u32::MAX,
+            ..Config::default()
+        };
+        for secret in [0, 1, u32::MAX - 1, u32::MAX] {
+            let summary = play(&config, secret, &mut io::sink()).unwrap();
+            assert_eq!(summary.outcome, Outcome::Won);
+            assert!(summary.attempts <= 33);
+        }
+    }
+
+    #[test]
+    fn top_of_u32() {
+        let config = Config {
+            min: u32::MAX - 5,
+            max: u32::MAX,
+            ..Config::default()
+        };
+        for secret in config.min..=config.max {
+            let summary = play(&config, secret, &mut io::sink()).unwrap();
+            assert_eq!(summary.outcome, Outcome::Won);
+            assert!(summary.attempts <= 3, "{secret}");
+        }
+    }
+
+    #[test]
+    fn hints_follow_the_answers() {
+        let mut bisection = Bisection::new(1, 100);
+        assert_eq!(
+            bisection.hint(),
+            "Hint: the number is between 1 and 100. Try 50, 7 more guesses at most will find it."
+        );
+        bisection.answer(50, Ordering::Greater);
+        bisection.answer(10, Ordering::Less);
+        assert_eq!(bisection, Bisection::new(11, 49));
+        assert_eq!(bisection.next_guess(), Some(30));
+        // A guess outside the range teaches nothing new.
+        bisection.answer(70, Ordering::Greater);
+        assert_eq!(bisection, Bisection::new(11, 49));
+        bisection.answer(12, Ordering::Greater);
+        assert_eq!(bisection.hint(), "Hint: it can only be 11.");
+        bisection.answer(11, Ordering::Less);
+        assert_eq!(bisection.remaining(), 0);
+
+        // Higher than the largest number or lower than 0 leaves nothing.
+        let mut bisection = Bisection::new(u32::MAX - 5, u32::MAX);
+        bisection.answer(u32::MAX, Ordering::Less);
+        assert_eq!(bisection.next_guess(), None);
+        let mut bisection = Bisection::new(0, 5);
+        bisection.answer(0, Ordering::Greater);
+        assert_eq!(bisection.next_guess(), None);
+    }
+}
@@ -0,0 +1,87 @@
+use std::time::{Duration, Instant};
+
+// Where the game gets the time from, so tests can use a clock that does not
+// need them to sleep.
+pub trait Clock {
+    // Time since the game started.
+    fn elapsed(&self) -> Duration;
+}
+
+// The real clock, started when it is made.
+pub struct SystemClock(Instant);
+
+impl SystemClock {
+    pub fn start() -> SystemClock {
+        SystemClock(Instant::now())
+    }
+}
+
+impl Clock for SystemClock {
+    fn elapsed(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+// A clock for tests that moves on by `step` every time it is read.
+#[cfg(test)]
+pub struct StepClock {
+    now: std::cell::Cell<Duration>,
+    step: Duration,
+}
+
+#[cfg(test)]
+impl StepClock {
+    pub fn new(step: Duration) -> StepClock {
+        StepClock {
+            now: std::cell::Cell::new(Duration::ZERO),
+            step,
+        }
+    }
+}
+
+#[cfg(test)]
+impl Clock for StepClock {
+    fn elapsed(&self) -> Duration {
+        self.now.set(self.now.get() + self.step);
+        self.now.get()
+    }
+}
+
+// Score of a timed challenge won in `attempts` guesses and `elapsed` time:
+// 1000 points shared by the guesses, plus 10 for every second left over.
+pub fn score(attempts: u32, elapsed: Duration, limit: Duration) -> u32 {
+    let left = limit.saturating_sub(elapsed).as_secs_f64();
+    1000 / attempts.max(1) + (10.0 * left).round() as u32
+}
+
+pub fn left(elapsed: Duration, limit: Duration) -> String {
+    format!(
+        "{:.1} seconds left.",
+        limit.saturating_sub(elapsed).as_secs_f64()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fewer_guesses_and_more_time_score_higher() {
+        let limit = Duration::from_secs(60);
+        assert_eq!(score(1, Duration::from_secs(10), limit), 1500);
+        assert_eq!(score(4, Duration::from_secs(10), limit), 750);
+        assert_eq!(score(4, Duration::from_millis(59_950), limit), 251);
+        assert_eq!(score(4, Duration::from_secs(90), limit), 250);
+        assert_eq!(
+            left(Duration::from_millis(12_345), limit),
+            "47.7 seconds left."
+        );
+    }
+
+    #[test]
+    fn step_clock() {
+        let clock = StepClock::new(Duration::from_secs(2));
+        assert_eq!(clock.elapsed(), Duration::from_secs(2));
+        assert_eq!(clock.elapsed(), Duration::from_secs(4));
+    }
+}
@@ -0,0 +1,320 @@
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+// A high-score file of one test, so tests neither see each other's games
+// nor write to the home directory.
+fn scores_file(test: &str) -> PathBuf {
+    env::temp_dir().join(format!("guessing_game_{test}_{}.json", std::process::id()))
+}
+
+fn game(test: &str, args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_guessing_game"));
+    command
+        .args(args)
+        .env("GUESSING_GAME_SCORES", scores_file(test))
+        .env("USER", "tester");
+    command
+}
+
+// Plays a whole game with `input` typed on stdin and returns what it printed.
+fn play(test: &str, args: &[&str], input: &str) -> String {
+    let mut child = game(test, args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Cannot start guessing_game");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "exit status {}", output.status);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// Finds the secret of a seeded game by bisection, reading each answer
+// before sending the next guess, and returns it with the guesses it took.
+fn solve(test: &str, args: &[&str], mut low: u32, mut high: u32) -> (u32, u32) {
+    let mut child = game(test, args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Cannot start guessing_game");
+    let mut input = child.stdin.take().unwrap();
+    let mut output = BufReader::new(child.stdout.take().unwrap());
+    let mut guesses = 0;
+    loop {
+        let guess = low + (high - low) / 2;
+        writeln!(input, "{guess}").unwrap();
+        guesses += 1;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            assert_ne!(output.read_line(&mut line).unwrap(), 0, "the game ended");
+            if line.starts_with("Your guess") || line.starts_with("You've guessed") {
+                break;
+            }
+        }
+        match line.trim() {
+            "Your guess is too low!" => low = guess + 1,
+            "Your guess is too big!" => high = guess - 1,
+            _ => {
+                drop(input);
+                assert!(child.wait().unwrap().success());
+                return (guess, guesses);
+            }
+        }
+    }
+}
+
+#[test]
+fn same_seed_same_game() {
+    let input = "50\n25\n75\n12\n88\n";
+    let first = play("same_seed", &["--seed", "7"], input);
+    assert_eq!(first, play("same_seed", &["--seed", "7"], input));
+    assert!(first.starts_with("Welcome to the guessing game!\nPick a number from 1 to 100!"));
+}
+
+#[test]
+fn bisection_finds_the_secret() {
+    let args = ["--seed", "2024", "--difficulty", "hard"];
+    let (secret, guesses) = solve("bisection", &args, 1, 1000);
+    assert!(guesses <= 10);
+    // Replaying the seed, the secret is right on the first try.
+    let output = play("bisection", &args, &format!("{secret}\n"));
+    assert!(output.ends_with("You've guessed the number, bravo!!!\n"));
+}
+
+#[test]
+fn loss_reveals_the_secret() {
+    let args = [
+        "--seed",
+        "11",
+        "--min",
+        "1",
+        "--max",
+        "10",
+        "--max-attempts",
+        "1",
+    ];
+    let (secret, _) = solve(
+        "loss",
+        &["--seed", "11", "--min", "1", "--max", "10"],
+        1,
+        10,
+    );
+    let wrong = if secret == 1 { 2 } else { 1 };
+    let output = play("loss", &args, &format!("{wrong}\n"));
+    assert!(output.ends_with(&format!(
+        "Out of attempts, you lose! The number was {secret}.\n"
+    )));
+}
+
+#[test]
+fn rejects_invalid_configuration() {
+    let output = game("invalid", &["--min", "100", "--max", "1"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("Error: --min (100) must be smaller than --max (1)"));
+}
+
+#[test]
+fn records_scores() {
+    let file = scores_file("scores");
+    let _ = fs::remove_file(&file);
+    let args = ["--difficulty", "easy", "--name", "ann"];
+    let (_, guesses) = solve("scores", &args, 1, 50);
+    // One guess out of four billion numbers is as good as a sure loss.
+    play(
+        "scores",
+        &[
+            "--max",
+            "4294967295",
+            "--max-attempts",
+            "1",
+            "--name",
+            "ann",
+        ],
+        "1\n",
+    );
+    // An unfinished game is not recorded.
+    play("scores", &["--name", "ann"], "");
+
+    let output = game("scores", &["scores", "--name", "ann"])
+        .output()
+        .unwrap();
+    fs::remove_file(&file).unwrap();
+    assert!(output.status.success());
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        report.starts_with("Leaderboard, easy:\n   1. ann"),
+        "{report}"
+    );
+    assert!(report.contains(&format!("{guesses:>3} attempts")));
+    assert!(!report.contains("custom"));
+    assert!(report.contains("ann: 2 games, 1 won (50%)"));
+    assert!(report.contains("winning streak 0, best 1"));
+}
+
+#[test]
+fn seeded_games_are_not_recorded() {
+    let file = scores_file("seeded");
+    let _ = fs::remove_file(&file);
+    let args = ["--seed", "5", "--difficulty", "easy", "--name", "ann"];
+    let (secret, _) = solve("seeded", &args, 1, 50);
+    // Replaying the seed finds the number at once.
+    let output = play("seeded", &args, &format!("{secret}\n"));
+    assert!(output.contains("You've guessed the number, bravo!!!"));
+    assert!(!file.exists());
+}
+
+#[test]
+fn auto_mode_finds_the_secret() {
+    let output = play(
+        "auto",
+        &["--auto", "--seed", "9", "--difficulty", "hard"],
+        "",
+    );
+    assert!(output.starts_with(
+        "The computer looks for a number from 1 to 1000, which takes at most 10 guesses.\n"
+    ));
+    assert!(output.contains(" guesses (worst case 10).\n"));
+    // The computer's games are not recorded.
+    assert!(!scores_file("auto").exists());
+
+    let output = play(
+        "auto",
+        &["--auto", "--min", "4294967290", "--max", "4294967295"],
+        "",
+    );
+    assert!(output.contains(" guesses (worst case 3).\n"));
+}
+
+#[test]
+fn reverse_mode() {
+    let output = play(
+        "reverse",
+        &["--reverse", "--max", "10"],
+        "higher\nlower\ncorrect\n",
+    );
+    assert!(output.starts_with("Think of a number from 1 to 10 and I will guess it."));
+    assert!(output.ends_with("Got it, your number is 6! It took me 3 guesses.\n"));
+
+    let output = play(
+        "reverse",
+        &["--reverse", "--max", "10"],
+        "higher\nlower\nlower\n",
+    );
+    assert!(output.ends_with(
+        "That can't be right: you said higher than 5 and lower than 6, no number fits both.\n"
+    ));
+}
+
+#[test]
+fn proximity_mode() {
+    // Seed 1 picks 83.
+    let output = play(
+        "proximity",
+        &["--proximity", "--seed", "1"],
+        "50\n70\n80\n83\n",
+    );
+    assert!(output.contains(
+        "Cold!\nWarm, warmer than 50.\nHot, warmer than 70.\nYou've guessed the number, bravo!!!\n"
+    ));
+}
+
+#[test]
+fn timed_challenge() {
+    // Seed 1 picks 83, found at once long before the minute is over.
+    let output = play("timed", &["--time-limit", "60", "--seed", "1"], "50\n83\n");
+    assert!(output.contains("You have 60 seconds, the clock is running!\n"));
+    assert!(output.contains(" seconds left.\n"));
+    assert!(output.contains("\nScore: "));
+    assert!(output.contains(" (2 guesses in "));
+}
+
+#[test]
+fn code_breaking() {
+    // The solver shows the code of a seeded game, which a player then types.
+    let output = play("code", &["code", "--colors", "--seed", "4", "--auto"], "");
+    assert!(output.starts_with("Guess 1: "));
+    let last = output.lines().rev().nth(1).unwrap();
+    let code = &last[9..13];
+    assert!(output.contains("Broke the code in "));
+
+    let output = play(
+        "code",
+        &["code", "--colors", "--seed", "4"],
+        &format!("RGB\n{code}\n"),
+    );
+    assert!(output.starts_with("Break the code of 4 symbols from RGBYOP, symbols may repeat.\n"));
+    assert!(output.contains("the code has 4 symbols, you typed 3\n"));
+    assert!(output.ends_with("You broke the code in 1 guess, bravo!!!\n"));
+
+    let status = game("code", &["code", "--length", "11"]).status().unwrap();
+    assert_eq!(status.code(), Some(2));
+}
+
+#[test]
+fn multiplayer_on_localhost() {
+    let mut server = game(
+        "multiplayer",
+        &[
+            "serve",
+            "--bind",
+            "127.0.0.1:0",
+            "--max",
+            "10",
+            "--seed",
+            "3",
+        ],
+    )
+    .stdout(Stdio::piped())
+    .spawn()
+    .expect("Cannot start the server");
+    let mut log = BufReader::new(server.stdout.take().unwrap());
+    let mut line = String::new();
+    log.read_line(&mut line).unwrap();
+    let address = line
+        .trim()
+        .strip_prefix("Waiting for 2 players on ")
+        .expect("no address")
+        .to_string();
+
+    // Between them the two players try every number, so one of them wins.
+    let players: Vec<_> = [("ann", "1\n3\n5\n7\n9\n"), ("bob", "2\n4\n6\n8\n10\n")]
+        .into_iter()
+        .map(|(name, guesses)| {
+            let mut child = game("multiplayer", &["join", &address, "--name", name])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Cannot start a player");
+            child
+                .stdin
+                .take()
+                .unwrap()
+                .write_all(guesses.as_bytes())
+                .unwrap();
+            child
+        })
+        .collect();
+    for player in players {
+        let output = player.wait_with_output().unwrap();
+        assert!(output.status.success());
+        let output = String::from_utf8(output.stdout).unwrap();
+        assert!(output.contains("Players: "), "{output}");
+        assert!(output.contains(" wins!\n"), "{output}");
+    }
+    let mut rest = String::new();
+    std::io::Read::read_to_string(&mut log, &mut rest).unwrap();
+    assert!(rest.ends_with(" wins!\n"), "{rest}");
+    assert!(server.wait().unwrap().success());
+}